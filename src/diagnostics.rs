@@ -0,0 +1,183 @@
+use crate::input;
+use crate::interpreter;
+use crate::parser;
+use crate::resolver;
+use crate::scanner;
+
+use std::fmt::Write;
+
+/// Where a diagnostic points: the (1-based) line, the (0-based) column of the first
+/// offending character, and how many characters to underline.
+#[derive(Debug, Clone, Copy)]
+pub struct Span {
+    pub line: usize,
+    pub col: i64,
+    pub len: usize,
+}
+
+impl Span {
+    /// Tokens record the column of their last character; point at the first one instead.
+    /// Multi-line tokens (strings) are underlined on their last line only.
+    pub fn of_token(tok: &scanner::Token) -> Span {
+        let last_line = match tok.lexeme.iter().rposition(|c| *c == '\n') {
+            Some(newline) => &tok.lexeme[newline + 1..],
+            None => &tok.lexeme[..],
+        };
+        let len = last_line.len().max(1);
+        Span {
+            line: tok.line,
+            col: tok.col - (len as i64 - 1),
+            len,
+        }
+    }
+
+    pub fn at(line: usize, col: i64) -> Span {
+        Span { line, col, len: 1 }
+    }
+}
+
+pub fn scanner_error(err: &scanner::Error) -> (String, Option<Span>) {
+    (err.what.clone(), Some(Span::at(err.line, err.col)))
+}
+
+pub fn parser_error(err: &parser::Error) -> (String, Option<Span>) {
+    let span = match err {
+        parser::Error::UnexpectedToken(tok) | parser::Error::LoopControlOutsideLoop(tok) => {
+            Span::of_token(tok)
+        }
+        parser::Error::TokenMismatch { found, .. } => Span::of_token(found),
+        parser::Error::MaxParamsExceeded { line, col, .. }
+        | parser::Error::InvalidAssignment { line, col }
+        | parser::Error::TooManyArguments { line, col }
+        | parser::Error::ExpectedExpression { line, col, .. }
+        | parser::Error::InvalidTokenInUnaryOp { line, col, .. }
+        | parser::Error::InvalidTokenInBinaryOp { line, col, .. } => Span::at(*line, *col),
+    };
+    (format!("{:?}", err), Some(span))
+}
+
+pub fn resolver_error(err: &resolver::Error) -> (String, Option<Span>) {
+    let span = match err {
+        resolver::Error::ReadLocalInOwnInitializer { name, line, col } => Span {
+            line: *line,
+            col: *col - (name.chars().count() as i64 - 1),
+            len: name.chars().count(),
+        },
+        resolver::Error::ReturnNotInFun { line, col }
+        | resolver::Error::ThisOutsideClass { line, col } => Span::at(*line, *col),
+    };
+    (format!("{:?}", err), Some(span))
+}
+
+pub fn runtime_error(err: &interpreter::RuntimeError) -> (String, Option<Span>) {
+    let name = match err {
+        interpreter::RuntimeError::UndefinedVariable { name, .. }
+        | interpreter::RuntimeError::UndefinedButDeclared { name, .. }
+        | interpreter::RuntimeError::AssignToUndeclared { name, .. }
+        | interpreter::RuntimeError::ClassInheritsFromItself { name, .. } => Some(name),
+        interpreter::RuntimeError::NoSuchAttribute { attr, .. }
+        | interpreter::RuntimeError::NoSuchModuleMember { name: attr, .. } => Some(attr),
+        _ => None,
+    };
+    let len = match err {
+        interpreter::RuntimeError::ImportFailed { len, .. }
+        | interpreter::RuntimeError::ImportCycle { len, .. } => *len,
+        _ => name.map_or(1, |name| name.chars().count()),
+    };
+    let span = err.location().map(|loc| Span {
+        line: loc.line,
+        col: loc.col - (len as i64 - 1),
+        len,
+    });
+    (err.to_string(), span)
+}
+
+pub fn source_name(source: &input::Source) -> String {
+    match source {
+        input::Source::Literal => String::from("<command line>"),
+        input::Source::Repl => String::from("<repl>"),
+        input::Source::File(name) => name.clone(),
+    }
+}
+
+/// Renders an error in the style
+///
+/// ```text
+/// error: expected ...
+///  --> file.lox:3:9
+///   |
+/// 3 | छाप (1 + ;
+///   |          ^
+/// ```
+///
+/// `first_line` is the line number of the first line of `input.content`, for inputs (such
+/// as REPL entries) whose tokens are numbered relative to a larger session.
+pub fn render(
+    input: &input::Input,
+    first_line: usize,
+    kind: &str,
+    message: &str,
+    span: Option<Span>,
+) -> String {
+    let mut res = String::new();
+    writeln!(&mut res, "{}: {}", kind, message).unwrap();
+
+    let name = source_name(&input.source);
+    let span = match span {
+        Some(span) => span,
+        None => {
+            writeln!(&mut res, " --> {}", name).unwrap();
+            return res;
+        }
+    };
+
+    writeln!(&mut res, " --> {}:{}:{}", name, span.line, span.col.max(0) + 1).unwrap();
+
+    let source_line = span
+        .line
+        .checked_sub(first_line)
+        .and_then(|idx| input.content.lines().nth(idx));
+
+    if let Some(source_line) = source_line {
+        let gutter = " ".repeat(span.line.to_string().len());
+        let col = span.col.max(0) as usize;
+        let padding: String = source_line
+            .chars()
+            .take(col)
+            .filter(|c| !is_zero_width(*c))
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        let underline_len = source_line
+            .chars()
+            .skip(col)
+            .take(span.len)
+            .filter(|c| !is_zero_width(*c))
+            .count()
+            .max(1);
+
+        writeln!(&mut res, "{} |", gutter).unwrap();
+        writeln!(&mut res, "{} | {}", span.line, source_line).unwrap();
+        writeln!(
+            &mut res,
+            "{} | {}{}",
+            gutter,
+            padding,
+            "^".repeat(underline_len)
+        )
+        .unwrap();
+    }
+
+    res
+}
+
+// Devanagari combining marks that terminals draw on top of the previous character.
+fn is_zero_width(c: char) -> bool {
+    matches!(c,
+        '\u{0900}'..='\u{0902}'
+        | '\u{093A}'
+        | '\u{093C}'
+        | '\u{0941}'..='\u{0948}'
+        | '\u{094D}'
+        | '\u{0951}'..='\u{0957}'
+        | '\u{0962}'..='\u{0963}')
+}
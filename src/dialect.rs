@@ -0,0 +1,231 @@
+//! Keyword dialects: extra spellings for keywords, read from a TOML file.
+//!
+//! ```toml
+//! [keywords]
+//! var = "घोषणा"
+//! fun = ["कार्य", "function"]
+//! ```
+//!
+//! Keys are the English names of the keywords below, or any spelling the scanner
+//! already knows (`chap`, or `"छाप"` in quotes, as TOML wants for non-ASCII keys).
+//! A program picks its dialect with `--dialect file` or with a pragma on its first
+//! line, `// बोली: file.toml` (or `// dialect: file.toml`), resolved relative to the
+//! program.
+
+use crate::messages;
+use crate::scanner;
+
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+const PRAGMAS: [&str; 2] = ["बोली:", "dialect:"];
+
+const KEYWORD_NAMES: [(&str, scanner::TokenType); 23] = [
+    ("and", scanner::TokenType::And),
+    ("break", scanner::TokenType::Break),
+    ("catch", scanner::TokenType::Catch),
+    ("class", scanner::TokenType::Class),
+    ("continue", scanner::TokenType::Continue),
+    ("else", scanner::TokenType::Else),
+    ("false", scanner::TokenType::False),
+    ("finally", scanner::TokenType::Finally),
+    ("for", scanner::TokenType::For),
+    ("fun", scanner::TokenType::Fun),
+    ("if", scanner::TokenType::If),
+    ("import", scanner::TokenType::Import),
+    ("nil", scanner::TokenType::Nil),
+    ("or", scanner::TokenType::Or),
+    ("print", scanner::TokenType::Print),
+    ("return", scanner::TokenType::Return),
+    ("super", scanner::TokenType::Super),
+    ("this", scanner::TokenType::This),
+    ("throw", scanner::TokenType::Throw),
+    ("true", scanner::TokenType::True),
+    ("try", scanner::TokenType::Try),
+    ("var", scanner::TokenType::Var),
+    ("while", scanner::TokenType::While),
+];
+
+#[derive(Debug, Clone, Default)]
+pub struct Dialect {
+    /// Spellings to accept on top of the built-in ones, and the keyword each stands for.
+    pub keywords: Vec<(String, scanner::TokenType)>,
+}
+
+#[derive(Debug)]
+pub enum Error {
+    Unreadable {
+        path: String,
+        reason: String,
+    },
+    Invalid {
+        path: String,
+        reason: String,
+    },
+    NoKeywords {
+        path: String,
+    },
+    NotAString {
+        path: String,
+        keyword: String,
+    },
+    UnknownKeyword {
+        path: String,
+        keyword: String,
+    },
+    InvalidSpelling {
+        path: String,
+        spelling: String,
+    },
+    Collision {
+        path: String,
+        spelling: String,
+        first: scanner::TokenType,
+        second: scanner::TokenType,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use messages::Message;
+
+        let message = match self {
+            Error::Unreadable { path, reason } => Message::DialectUnreadable { path, reason },
+            Error::Invalid { path, reason } => Message::DialectInvalid { path, reason },
+            Error::NoKeywords { path } => Message::DialectNoKeywords(path),
+            Error::NotAString { path, keyword } => Message::DialectNotAString { path, keyword },
+            Error::UnknownKeyword { path, keyword } => {
+                Message::DialectUnknownKeyword { path, keyword }
+            }
+            Error::InvalidSpelling { path, spelling } => {
+                Message::DialectInvalidSpelling { path, spelling }
+            }
+            Error::Collision {
+                path,
+                spelling,
+                first,
+                second,
+            } => Message::DialectCollision {
+                path,
+                spelling,
+                first: *first,
+                second: *second,
+            },
+        };
+        write!(f, "{}", message)
+    }
+}
+
+pub fn load(path: &Path) -> Result<Dialect, Error> {
+    let path_name = path.display().to_string();
+    let content = fs::read_to_string(path).map_err(|err| Error::Unreadable {
+        path: path_name.clone(),
+        reason: err.to_string(),
+    })?;
+    let value: toml::Value = content
+        .parse()
+        .map_err(|err: toml::de::Error| Error::Invalid {
+            path: path_name.clone(),
+            reason: err.to_string(),
+        })?;
+    let table = match value.get("keywords") {
+        Some(toml::Value::Table(table)) => table,
+        _ => return Err(Error::NoKeywords { path: path_name }),
+    };
+
+    let builtin = scanner::keywords();
+    let mut dialect = Dialect::default();
+
+    for (keyword, spellings) in table.iter() {
+        let ty = match KEYWORD_NAMES.iter().find(|(name, _)| name == keyword) {
+            Some((_, ty)) => *ty,
+            None => match builtin.get(keyword) {
+                Some(ty) => *ty,
+                None => {
+                    return Err(Error::UnknownKeyword {
+                        path: path_name,
+                        keyword: keyword.clone(),
+                    })
+                }
+            },
+        };
+        let not_a_string = || Error::NotAString {
+            path: path_name.clone(),
+            keyword: keyword.clone(),
+        };
+        let spellings: Vec<&str> = match spellings {
+            toml::Value::String(spelling) => vec![spelling],
+            toml::Value::Array(spellings) => spellings
+                .iter()
+                .map(|spelling| spelling.as_str().ok_or_else(not_a_string))
+                .collect::<Result<_, _>>()?,
+            _ => return Err(not_a_string()),
+        };
+
+        for spelling in spellings {
+            if !scanner::is_identifier(spelling) {
+                return Err(Error::InvalidSpelling {
+                    path: path_name,
+                    spelling: String::from(spelling),
+                });
+            }
+            let existing = builtin.get(spelling).copied().or_else(|| {
+                dialect
+                    .keywords
+                    .iter()
+                    .find(|(other, _)| other == spelling)
+                    .map(|(_, other_ty)| *other_ty)
+            });
+            match existing {
+                Some(existing_ty) if existing_ty != ty => {
+                    return Err(Error::Collision {
+                        path: path_name,
+                        spelling: String::from(spelling),
+                        first: existing_ty,
+                        second: ty,
+                    })
+                }
+                Some(_) => {}
+                None => dialect.keywords.push((String::from(spelling), ty)),
+            }
+        }
+    }
+
+    Ok(dialect)
+}
+
+/// The spellings `dialect` adds, if there is one.
+pub fn extra_keywords(dialect: Option<&Dialect>) -> &[(String, scanner::TokenType)] {
+    match dialect {
+        Some(dialect) => &dialect.keywords,
+        None => &[],
+    }
+}
+
+/// The file named by a dialect pragma on the first line of `source`, if any.
+pub fn pragma(source: &str) -> Option<&str> {
+    let comment = source
+        .lines()
+        .next()?
+        .trim()
+        .strip_prefix("//")?
+        .trim_start();
+    PRAGMAS
+        .iter()
+        .find_map(|pragma| comment.strip_prefix(pragma))
+        .map(str::trim)
+}
+
+/// The dialect to scan `source` with: the one its pragma names, relative to `dir`, or
+/// else `default`.
+pub fn for_source(
+    source: &str,
+    dir: &Path,
+    default: Option<&Dialect>,
+) -> Result<Option<Dialect>, Error> {
+    match pragma(source) {
+        Some(file) => load(&dir.join(file)).map(Some),
+        None => Ok(default.cloned()),
+    }
+}
@@ -0,0 +1,61 @@
+//! Lists a program's functions and classes with their `///` docs.
+
+use crate::expr;
+
+const INDENT: &str = "    ";
+
+/// Every top-level function and class in `stmts`, and the methods of each class.
+pub fn render(stmts: &[expr::Stmt]) -> String {
+    let mut res = String::new();
+
+    for stmt in stmts.iter() {
+        match stmt {
+            expr::Stmt::FunDecl(fun_decl) => {
+                entry(&mut res, 0, &signature(None, fun_decl), &fun_decl.doc)
+            }
+            expr::Stmt::ClassDecl(class_decl) => {
+                let class_name = &class_decl.name.name;
+                let header = match &class_decl.superclass {
+                    Some(superclass) => format!("{} < {}", class_name, superclass.name),
+                    None => class_name.clone(),
+                };
+                entry(&mut res, 0, &header, &class_decl.doc);
+                for method in class_decl.methods.iter() {
+                    entry(&mut res, 1, &signature(Some(class_name), method), &method.doc);
+                }
+            }
+            _ => {}
+        }
+    }
+    res
+}
+
+fn signature(class_name: Option<&str>, fun_decl: &expr::FunDecl) -> String {
+    let params: Vec<&str> = fun_decl
+        .params
+        .iter()
+        .map(|param| param.name.as_str())
+        .collect();
+    match class_name {
+        Some(class_name) => format!("{}.{}({})", class_name, fun_decl.name.name, params.join(", ")),
+        None => format!("{}({})", fun_decl.name.name, params.join(", ")),
+    }
+}
+
+fn entry(res: &mut String, depth: usize, header: &str, doc: &Option<String>) {
+    if !res.is_empty() {
+        res.push('\n');
+    }
+    res.push_str(&INDENT.repeat(depth));
+    res.push_str(header);
+    res.push('\n');
+    if let Some(doc) = doc {
+        for line in doc.lines() {
+            if !line.is_empty() {
+                res.push_str(&INDENT.repeat(depth + 1));
+                res.push_str(line);
+            }
+            res.push('\n');
+        }
+    }
+}
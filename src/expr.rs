@@ -12,6 +12,37 @@ pub enum Expr {
     Logical(Box<Expr>, LogicalOp, Box<Expr>),
     Set(Box<Expr>, Symbol, Box<Expr>),
     Super(SourceLocation, Symbol),
+    Lambda(LambdaDecl),
+    List(Vec<Expr>),
+    /// `"a ${b} c"`: the literal pieces and embedded expressions, in order.
+    Interpolation(Vec<Expr>),
+    Dict {
+        entries: Vec<(Expr, Expr)>,
+        source_location: SourceLocation,
+    },
+    Subscript {
+        value: Box<Expr>,
+        slice: Box<Expr>,
+        source_location: SourceLocation,
+    },
+    SetItem {
+        lhs: Box<Expr>,
+        slice: Box<Expr>,
+        rhs: Box<Expr>,
+        source_location: SourceLocation,
+    },
+    /// `target op= value`; the target is a `Variable`, `Get` or `Subscript`.
+    CompoundAssign {
+        target: Box<Expr>,
+        op: BinaryOp,
+        value: Box<Expr>,
+    },
+    /// `target++` or `target--`, which evaluates to the value from before; `op` is `Plus`
+    /// or `Minus` and the target is a `Variable`, `Get` or `Subscript`.
+    Increment {
+        target: Box<Expr>,
+        op: BinaryOp,
+    },
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -33,8 +64,19 @@ pub struct Symbol {
     pub col: i64,
 }
 
+impl Symbol {
+    pub fn location(&self) -> SourceLocation {
+        SourceLocation {
+            line: self.line,
+            col: self.col,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct FunDecl {
+    /// The `///` comments before the function, without the slashes.
+    pub doc: Option<String>,
     pub name: Symbol,
     pub params: Vec<Symbol>,
     pub body: Vec<Stmt>,
@@ -44,10 +86,12 @@ pub struct FunDecl {
 pub struct LambdaDecl {
     pub params: Vec<Symbol>,
     pub body: Vec<Stmt>,
+    pub source_location: SourceLocation,
 }
 
 #[derive(Debug, Clone)]
 pub struct ClassDecl {
+    pub doc: Option<String>,
     pub name: Symbol,
     pub superclass: Option<Symbol>,
     pub methods: Vec<FunDecl>,
@@ -63,7 +107,31 @@ pub enum Stmt {
     VarDecl(Symbol, Option<Expr>),
     Block(Vec<Stmt>),
     Return(SourceLocation, Option<Expr>),
-    While(Expr, Box<Stmt>),
+    // the optional increment of a desugared `for` loop runs after every iteration, even
+    // one cut short by continue
+    While(Expr, Box<Stmt>, Option<Expr>),
+    Break,
+    Continue,
+    Throw(SourceLocation, Expr),
+    Try {
+        body: Vec<Stmt>,
+        catch: Option<Catch>,
+        finally: Option<Vec<Stmt>>,
+    },
+    // `path` is relative to the importing file; `source_location` and `path_len` are
+    // those of the path literal
+    Import {
+        name: Symbol,
+        path: String,
+        source_location: SourceLocation,
+        path_len: usize,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct Catch {
+    pub name: Symbol,
+    pub body: Vec<Stmt>,
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -91,6 +159,8 @@ pub enum BinaryOpTy {
     Minus,
     Star,
     Slash,
+    Percent,
+    StarStar,
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -0,0 +1,665 @@
+//! Pretty-prints programs in one canonical layout.
+//!
+//! The printer walks the syntax tree together with the program's tokens, so keywords,
+//! numbers and strings keep the spelling they were written with; only the whitespace
+//! between tokens changes. Comments stay next to the token they were written next to:
+//! on their own line before it, or on its line. Blank lines between statements are kept,
+//! one at most.
+
+use crate::expr;
+use crate::parser;
+use crate::scanner;
+use crate::scanner::TokenType;
+
+const INDENT: &str = "    ";
+
+#[derive(Debug)]
+pub enum Error {
+    Scan(scanner::Error),
+    Parse(Vec<parser::Error>),
+}
+
+pub fn format(source: &str, extra_keywords: &[(String, TokenType)]) -> Result<String, Error> {
+    let tokens = scanner::scan_lossless(source.to_string(), extra_keywords).map_err(Error::Scan)?;
+    let comments = comments(&tokens);
+    let stmts = parser::parse(tokens.clone()).map_err(Error::Parse)?;
+
+    let mut formatter = Formatter {
+        tokens: &tokens,
+        comments: &comments,
+        pos: 0,
+        comment_pos: 0,
+        out: String::new(),
+        indent: 0,
+        at_line_start: true,
+        hanging: false,
+        last_line: 0,
+        blank_allowed: false,
+    };
+    for stmt in stmts.iter() {
+        formatter.stmt(stmt);
+    }
+    formatter.finish();
+
+    Ok(formatter.out)
+}
+
+struct Comment {
+    /// The whole comment, `//` or `/* */` included, without trailing whitespace.
+    text: String,
+    /// The lines it starts and ends on.
+    line: usize,
+    end_line: usize,
+    /// The token it is trivia of, and whether it comes after that token.
+    token: usize,
+    trailing: bool,
+}
+
+/// The comments in the trivia of `tokens`, in order.
+fn comments(tokens: &[scanner::Token]) -> Vec<Comment> {
+    let mut res = Vec::new();
+    let mut line = 1;
+
+    for (idx, tok) in tokens.iter().enumerate() {
+        for trivia in tok.leading_trivia() {
+            line = add_comment(&mut res, trivia, line, idx, false);
+        }
+        line = tok.line;
+        for trivia in tok.trailing_trivia() {
+            line = add_comment(&mut res, trivia, line, idx, true);
+        }
+    }
+    res
+}
+
+/// Adds `trivia` if it is a comment, and returns the line it ends on.
+fn add_comment(
+    comments: &mut Vec<Comment>,
+    trivia: &scanner::Trivia,
+    line: usize,
+    token: usize,
+    trailing: bool,
+) -> usize {
+    let end_line = line + trivia.text().matches('\n').count();
+    match trivia {
+        scanner::Trivia::LineComment(text)
+        | scanner::Trivia::BlockComment(text)
+        | scanner::Trivia::DocComment(text) => comments.push(Comment {
+            text: String::from(text.trim_end()),
+            line,
+            end_line,
+            token,
+            trailing,
+        }),
+        scanner::Trivia::Whitespace(_) | scanner::Trivia::Newline => {}
+    }
+    end_line
+}
+
+struct Formatter<'a> {
+    tokens: &'a [scanner::Token],
+    comments: &'a [Comment],
+    /// The next token and comment to print.
+    pos: usize,
+    comment_pos: usize,
+    out: String,
+    indent: usize,
+    at_line_start: bool,
+    /// Whether the current line continues a statement that a comment broke, and so is
+    /// indented one more level.
+    hanging: bool,
+    /// The source line of the last token or comment printed.
+    last_line: usize,
+    /// Whether a blank line from the source may be kept here (not right after a `{`).
+    blank_allowed: bool,
+}
+
+/// The line a token starts on; `Token::line` is the line it ends on.
+fn start_line(tok: &scanner::Token) -> usize {
+    tok.line - tok.lexeme.iter().filter(|c| **c == '\n').count()
+}
+
+impl<'a> Formatter<'a> {
+    fn peek(&self) -> TokenType {
+        self.tokens[self.pos].ty
+    }
+
+    fn write(&mut self, text: &str) {
+        if self.at_line_start {
+            for _ in 0..self.indent + self.hanging as usize {
+                self.out.push_str(INDENT);
+            }
+            self.at_line_start = false;
+        }
+        self.out.push_str(text);
+    }
+
+    fn space(&mut self) {
+        if !self.at_line_start && !self.out.ends_with(' ') {
+            self.out.push(' ');
+        }
+    }
+
+    fn newline(&mut self) {
+        if !self.at_line_start {
+            let trimmed = self.out.trim_end_matches(' ').len();
+            self.out.truncate(trimmed);
+            self.out.push('\n');
+            self.at_line_start = true;
+        }
+    }
+
+    /// Keeps a blank line before something on source line `line` if there was one.
+    fn blank_line_before(&mut self, line: usize) {
+        if self.blank_allowed && self.at_line_start && line > self.last_line + 1 {
+            self.out.push('\n');
+            self.blank_allowed = false;
+        }
+    }
+
+    /// The next comment if it is trivia of token `token`, before or after it.
+    fn comment_of(&self, token: usize, trailing: bool) -> Option<&'a Comment> {
+        self.comments
+            .get(self.comment_pos)
+            .filter(|comment| comment.token == token && comment.trailing == trailing)
+    }
+
+    /// Starts a new line for the next token, keeping a blank line before it (or before
+    /// its comments) if the source had one.
+    fn begin_line(&mut self) {
+        self.newline();
+        self.hanging = false;
+        let first_line = match self.comment_of(self.pos, false) {
+            Some(comment) => comment.line,
+            None => start_line(&self.tokens[self.pos]),
+        };
+        self.blank_line_before(first_line);
+        self.leading_comments(false);
+        self.hanging = false;
+        self.blank_line_before(start_line(&self.tokens[self.pos]));
+    }
+
+    /// Prints the comments before the next token: on their own lines, but for one on the
+    /// token's line, which stays there. With `hang`, comments on their own lines (and the
+    /// token after them) hang.
+    fn leading_comments(&mut self, hang: bool) {
+        let next_line = start_line(&self.tokens[self.pos]);
+        while let Some(comment) = self.comment_of(self.pos, false) {
+            if comment.end_line < next_line {
+                if hang {
+                    self.hanging = true;
+                }
+                self.newline();
+                self.blank_line_before(comment.line);
+                self.write(&comment.text);
+                self.newline();
+            } else {
+                self.write(&comment.text);
+                self.write(" ");
+            }
+            self.last_line = comment.end_line;
+            self.blank_allowed = true;
+            self.comment_pos += 1;
+        }
+    }
+
+    /// Prints the comments before a token that carries on the statement at its own indent,
+    /// like `अरु` or `{`, and takes back any hang for it.
+    fn clause(&mut self) {
+        if self.at_line_start || self.comment_of(self.pos, false).is_some() {
+            self.hanging = false;
+        }
+        self.leading_comments(false);
+        if self.at_line_start {
+            self.hanging = false;
+        }
+    }
+
+    fn token(&mut self, ty: TokenType) {
+        if self.peek() != ty {
+            panic!(
+                "internal error in formatter: expected {:?}, found {:?}",
+                ty, self.tokens[self.pos]
+            );
+        }
+        self.any_token();
+    }
+
+    fn any_token(&mut self) {
+        self.leading_comments(true);
+
+        let tok = &self.tokens[self.pos];
+        let lexeme: String = tok.lexeme.iter().collect();
+        self.write(&lexeme);
+        self.last_line = tok.line;
+
+        // comments after the token stay on its line
+        let mut commented = false;
+        while let Some(comment) = self.comment_of(self.pos, true) {
+            self.write(" ");
+            self.write(&comment.text);
+            self.last_line = comment.end_line;
+            self.comment_pos += 1;
+            commented = true;
+        }
+        self.pos += 1;
+
+        let next = &self.tokens[self.pos];
+        if commented {
+            if next.ty == TokenType::Eof || start_line(next) > self.last_line {
+                self.newline();
+                self.hanging = true;
+            } else {
+                self.write(" ");
+            }
+        }
+    }
+
+    fn finish(&mut self) {
+        while let Some(comment) = self.comment_of(self.pos, false) {
+            self.newline();
+            self.blank_line_before(comment.line);
+            self.write(&comment.text);
+            self.last_line = comment.end_line;
+            self.blank_allowed = true;
+            self.comment_pos += 1;
+        }
+        self.newline();
+    }
+
+    fn stmt(&mut self, stmt: &expr::Stmt) {
+        self.begin_line();
+        self.stmt_content(stmt);
+        self.newline();
+        self.hanging = false;
+        self.blank_allowed = true;
+    }
+
+    /// Prints a statement from wherever the output is, without the line breaks around it.
+    fn stmt_content(&mut self, stmt: &expr::Stmt) {
+        match stmt {
+            expr::Stmt::Block(stmts) if self.peek() == TokenType::For => {
+                self.for_loop(Some(&stmts[0]), &stmts[1])
+            }
+            expr::Stmt::While(..) if self.peek() == TokenType::For => self.for_loop(None, stmt),
+            expr::Stmt::Expr(expr) => {
+                self.expr(expr);
+                self.token(TokenType::Semicolon);
+            }
+            expr::Stmt::FunDecl(fun_decl) => {
+                self.token(TokenType::Fun);
+                self.space();
+                self.token(TokenType::Identifier);
+                self.params_and_body(&fun_decl.params, &fun_decl.body);
+            }
+            expr::Stmt::ClassDecl(class_decl) => {
+                self.token(TokenType::Class);
+                self.space();
+                self.token(TokenType::Identifier);
+                if class_decl.superclass.is_some() {
+                    self.space();
+                    self.token(TokenType::Less);
+                    self.space();
+                    self.token(TokenType::Identifier);
+                }
+                if self.open_brace(class_decl.methods.is_empty()) {
+                    for method in class_decl.methods.iter() {
+                        self.begin_line();
+                        self.token(TokenType::Identifier);
+                        self.params_and_body(&method.params, &method.body);
+                        self.newline();
+                        self.blank_allowed = true;
+                    }
+                    self.close_brace();
+                }
+            }
+            expr::Stmt::If(cond, then_branch, else_branch) => {
+                self.token(TokenType::If);
+                self.space();
+                self.token(TokenType::LeftParen);
+                self.expr(cond);
+                self.token(TokenType::RightParen);
+                let braced = self.peek() == TokenType::LeftBrace;
+                self.body(then_branch);
+                if let Some(else_branch) = else_branch {
+                    if braced {
+                        self.space();
+                    } else {
+                        self.newline();
+                    }
+                    self.clause();
+                    self.token(TokenType::Else);
+                    self.body(else_branch);
+                }
+            }
+            expr::Stmt::Print(expr) => {
+                self.token(TokenType::Print);
+                self.space();
+                self.expr(expr);
+                self.token(TokenType::Semicolon);
+            }
+            expr::Stmt::VarDecl(_, initializer) => {
+                self.token(TokenType::Var);
+                self.space();
+                self.token(TokenType::Identifier);
+                if let Some(initializer) = initializer {
+                    self.space();
+                    self.token(TokenType::Equal);
+                    self.space();
+                    self.expr(initializer);
+                }
+                self.token(TokenType::Semicolon);
+            }
+            expr::Stmt::Block(stmts) => self.block(stmts),
+            expr::Stmt::Return(_, value) => {
+                self.token(TokenType::Return);
+                if let Some(value) = value {
+                    self.space();
+                    self.expr(value);
+                }
+                self.token(TokenType::Semicolon);
+            }
+            expr::Stmt::While(cond, body, _) => {
+                self.token(TokenType::While);
+                self.space();
+                self.token(TokenType::LeftParen);
+                self.expr(cond);
+                self.token(TokenType::RightParen);
+                self.body(body);
+            }
+            expr::Stmt::Break | expr::Stmt::Continue => {
+                self.any_token();
+                self.token(TokenType::Semicolon);
+            }
+            expr::Stmt::Throw(_, expr) => {
+                self.token(TokenType::Throw);
+                self.space();
+                self.expr(expr);
+                self.token(TokenType::Semicolon);
+            }
+            expr::Stmt::Try {
+                body,
+                catch,
+                finally,
+            } => {
+                self.token(TokenType::Try);
+                self.block(body);
+                if let Some(catch) = catch {
+                    self.space();
+                    self.clause();
+                    self.token(TokenType::Catch);
+                    self.space();
+                    self.token(TokenType::LeftParen);
+                    self.token(TokenType::Identifier);
+                    self.token(TokenType::RightParen);
+                    self.block(&catch.body);
+                }
+                if let Some(finally) = finally {
+                    self.space();
+                    self.clause();
+                    self.token(TokenType::Finally);
+                    self.block(finally);
+                }
+            }
+            expr::Stmt::Import { .. } => {
+                self.token(TokenType::Import);
+                self.space();
+                self.token(TokenType::Identifier);
+                self.space();
+                self.token(TokenType::Equal);
+                self.space();
+                self.token(TokenType::String);
+                self.token(TokenType::Semicolon);
+            }
+        }
+    }
+
+    /// `for` loops are desugared by the parser; their tokens tell which parts were written.
+    fn for_loop(&mut self, initializer: Option<&expr::Stmt>, while_loop: &expr::Stmt) {
+        let (cond, body, increment) = match while_loop {
+            expr::Stmt::While(cond, body, increment) => (cond, body, increment),
+            _ => panic!("internal error in formatter: for loop without a while loop"),
+        };
+
+        self.token(TokenType::For);
+        self.space();
+        self.token(TokenType::LeftParen);
+        match initializer {
+            Some(initializer) => self.stmt_content(initializer),
+            None => self.token(TokenType::Semicolon),
+        }
+        if self.peek() != TokenType::Semicolon {
+            self.space();
+            self.expr(cond);
+        }
+        self.token(TokenType::Semicolon);
+        if let Some(increment) = increment {
+            self.space();
+            self.expr(increment);
+        }
+        self.token(TokenType::RightParen);
+        self.body(body);
+    }
+
+    /// The body of an `if`, `else` or loop: a block, or a statement on the same line.
+    fn body(&mut self, stmt: &expr::Stmt) {
+        match stmt {
+            expr::Stmt::Block(stmts) if self.peek() == TokenType::LeftBrace => self.block(stmts),
+            _ => {
+                self.space();
+                self.stmt_content(stmt);
+            }
+        }
+    }
+
+    fn block(&mut self, stmts: &[expr::Stmt]) {
+        if self.open_brace(stmts.is_empty()) {
+            for stmt in stmts.iter() {
+                self.stmt(stmt);
+            }
+            self.close_brace();
+        }
+    }
+
+    /// Prints a `{`, or `{}` if `empty` and there are no comments inside. Returns whether
+    /// the contents and `close_brace` should follow.
+    fn open_brace(&mut self, empty: bool) -> bool {
+        self.space();
+        self.clause();
+        let inside = self.comments[self.comment_pos..]
+            .iter()
+            .take_while(|comment| comment.token <= self.pos + 1)
+            .any(|comment| (comment.token == self.pos) == comment.trailing);
+        if empty && !inside {
+            self.token(TokenType::LeftBrace);
+            self.token(TokenType::RightBrace);
+            return false;
+        }
+        self.token(TokenType::LeftBrace);
+        self.indent += 1;
+        self.newline();
+        self.hanging = false;
+        self.blank_allowed = false;
+        true
+    }
+
+    fn close_brace(&mut self) {
+        // comments before the `}` belong inside the block
+        self.leading_comments(false);
+        self.indent -= 1;
+        self.newline();
+        self.hanging = false;
+        self.token(TokenType::RightBrace);
+    }
+
+    fn params_and_body(&mut self, params: &[expr::Symbol], body: &[expr::Stmt]) {
+        self.token(TokenType::LeftParen);
+        for idx in 0..params.len() {
+            if idx > 0 {
+                self.token(TokenType::Comma);
+                self.space();
+            }
+            self.token(TokenType::Identifier);
+        }
+        self.token(TokenType::RightParen);
+        self.block(body);
+    }
+
+    fn exprs(&mut self, exprs: &[expr::Expr]) {
+        for (idx, expr) in exprs.iter().enumerate() {
+            if idx > 0 {
+                self.token(TokenType::Comma);
+                self.space();
+            }
+            self.expr(expr);
+        }
+    }
+
+    fn expr(&mut self, expr: &expr::Expr) {
+        match expr {
+            expr::Expr::Literal(_) | expr::Expr::This(_) | expr::Expr::Variable(_) => {
+                self.any_token()
+            }
+            expr::Expr::Unary(_, operand) => {
+                self.any_token();
+                self.expr(operand);
+            }
+            expr::Expr::Increment { target, .. } => {
+                self.expr(target);
+                self.any_token();
+            }
+            expr::Expr::Binary(lhs, _, rhs)
+            | expr::Expr::Logical(lhs, _, rhs)
+            | expr::Expr::CompoundAssign {
+                target: lhs,
+                value: rhs,
+                ..
+            } => {
+                self.expr(lhs);
+                self.space();
+                self.any_token();
+                self.space();
+                self.expr(rhs);
+            }
+            expr::Expr::Call(callee, _, args) => {
+                self.expr(callee);
+                self.token(TokenType::LeftParen);
+                self.exprs(args);
+                self.token(TokenType::RightParen);
+            }
+            expr::Expr::Get(object, _) => {
+                self.expr(object);
+                self.token(TokenType::Dot);
+                self.token(TokenType::Identifier);
+            }
+            expr::Expr::Grouping(inner) => {
+                self.token(TokenType::LeftParen);
+                self.expr(inner);
+                self.token(TokenType::RightParen);
+            }
+            expr::Expr::Assign(_, value) => {
+                self.token(TokenType::Identifier);
+                self.space();
+                self.token(TokenType::Equal);
+                self.space();
+                self.expr(value);
+            }
+            expr::Expr::Set(object, _, value) => {
+                self.expr(object);
+                self.token(TokenType::Dot);
+                self.token(TokenType::Identifier);
+                self.space();
+                self.token(TokenType::Equal);
+                self.space();
+                self.expr(value);
+            }
+            expr::Expr::Super(_, _) => {
+                self.token(TokenType::Super);
+                self.token(TokenType::Dot);
+                self.token(TokenType::Identifier);
+            }
+            expr::Expr::Lambda(lambda_decl) => {
+                self.token(TokenType::Fun);
+                self.space();
+                self.params_and_body(&lambda_decl.params, &lambda_decl.body);
+            }
+            expr::Expr::List(elements) => {
+                self.token(TokenType::LeftBracket);
+                self.exprs(elements);
+                self.token(TokenType::RightBracket);
+            }
+            expr::Expr::Interpolation(parts) => {
+                // empty pieces of the string have no part of their own
+                let mut parts = parts.iter();
+                loop {
+                    let tok = &self.tokens[self.pos];
+                    let is_end = tok.ty == TokenType::InterpolationEnd;
+                    if let Some(scanner::Literal::Str(s)) = &tok.literal {
+                        if !s.is_empty() {
+                            parts.next();
+                        }
+                    }
+                    self.any_token();
+                    if is_end {
+                        break;
+                    }
+                    if let Some(part) = parts.next() {
+                        self.expr(part);
+                    }
+                }
+            }
+            expr::Expr::Dict { entries, .. } => {
+                self.token(TokenType::LeftBrace);
+                for (idx, (key, value)) in entries.iter().enumerate() {
+                    if idx > 0 {
+                        self.token(TokenType::Comma);
+                        self.space();
+                    }
+                    self.expr(key);
+                    self.token(TokenType::Colon);
+                    self.space();
+                    self.expr(value);
+                }
+                self.token(TokenType::RightBrace);
+            }
+            expr::Expr::Subscript { value, slice, .. } => {
+                self.expr(value);
+                self.token(TokenType::LeftBracket);
+                self.expr(slice);
+                self.token(TokenType::RightBracket);
+            }
+            expr::Expr::SetItem { lhs, slice, rhs, .. } => {
+                self.expr(lhs);
+                self.token(TokenType::LeftBracket);
+                self.expr(slice);
+                self.token(TokenType::RightBracket);
+                self.space();
+                self.token(TokenType::Equal);
+                self.space();
+                self.expr(rhs);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formatting_twice_is_a_no_op() {
+        let sources = [
+            "भार क=१;छाप क ;\r\nछाप \"a ${क+१} b\";",
+            "/// जोड्छ\nकाम जोड(a,b){ रिटन a+b; // योग\n}\n\n\n/* /* nested */ */ छाप जोड(१,२);",
+            "वर्ग A{m(){रिटन १;}} वर्ग B<A{ m(){ भार f=काम(){रिटन सुपर.m();}; रिटन f(); } }",
+            "यदि (क) // टिप्पणी\n छाप १; अरु छाप २;\nभार l = [१, // पहिलो\n२];",
+            "भुमरी(भार i=०;i<३;i++){ यदि(i==१){अर्को;} l[i]+=i; }",
+            "प्रयास { फाल \"x\"; } // पछि\nसमात (e) { छाप e.सन्देश; }\nअन्त्यमा { छाप १; } // अन्त्य",
+        ];
+        for source in sources.iter() {
+            let once = format(source, &[]).unwrap();
+            let twice = format(&once, &[]).unwrap();
+            assert_eq!(once, twice, "formatting {:?}", source);
+        }
+    }
+}
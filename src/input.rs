@@ -1,6 +1,7 @@
 #[derive(Debug)]
 pub enum Source {
     Literal,
+    Repl,
     File(String),
 }
 
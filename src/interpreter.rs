@@ -1,26 +1,45 @@
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::convert::TryInto;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 
+use crate::dialect;
+use crate::diagnostics;
 use crate::expr;
+use crate::expr::SourceLocation;
+use crate::input;
+use crate::messages;
+use crate::parser;
+use crate::resolver;
+use crate::scanner;
 
 use std::fmt;
 use std::fmt::Write;
 
 static INIT: &str = "सुरु";
 
+// runtime errors caught by समात are instances of this class
+static ERROR_CLASS: &str = "त्रुटि";
+static ERROR_MESSAGE: &str = "सन्देश";
+static ERROR_LINE: &str = "रेखा";
+static ERROR_COL: &str = "स्तम्भ";
+static ERROR_VALUE: &str = "मान";
+
 trait Callable {
     fn arity(&self, interpreter: &Interpreter) -> u8;
-    fn call(&self, interpreter: &mut Interpreter, args: &[Value]) -> Result<Value, String>;
+    fn call(&self, interpreter: &mut Interpreter, args: &[Value]) -> Result<Value, RuntimeError>;
 }
 
 #[derive(Clone)]
 pub struct NativeFunction {
     pub name: String,
     pub arity: u8,
-    pub callable: fn(&mut Interpreter, &[Value]) -> Result<Value, String>,
+    pub callable: fn(&mut Interpreter, &[Value]) -> Result<Value, RuntimeError>,
 }
 
 impl fmt::Debug for NativeFunction {
@@ -33,7 +52,7 @@ impl Callable for NativeFunction {
     fn arity(&self, _interpreter: &Interpreter) -> u8 {
         self.arity
     }
-    fn call(&self, interpreter: &mut Interpreter, args: &[Value]) -> Result<Value, String> {
+    fn call(&self, interpreter: &mut Interpreter, args: &[Value]) -> Result<Value, RuntimeError> {
         (self.callable)(interpreter, args)
     }
 }
@@ -48,103 +67,73 @@ pub struct LoxFunction {
     pub this_binding: Option<Box<Value>>,
     pub superclass: Option<u64>,
     pub is_initializer: bool,
+    // the resolved locals and module of the file the function was written in
+    pub locals: Rc<resolver::Locals>,
+    pub module: Option<u64>,
 }
 
 impl Callable for LoxFunction {
     fn arity(&self, _interpreter: &Interpreter) -> u8 {
         self.parameters.len().try_into().unwrap()
     }
-    fn call(&self, interpreter: &mut Interpreter, args: &[Value]) -> Result<Value, String> {
-        let args_env: HashMap<_, _> = self
-            .parameters
-            .iter()
-            .zip(args.iter())
-            .map(|(param, arg)| {
-                (
-                    param.name.clone(),
-                    (
-                        Some(arg.clone()),
-                        SourceLocation {
-                            line: param.line,
-                            col: param.col,
-                        },
-                    ),
-                )
-            })
-            .collect();
-
+    fn call(&self, interpreter: &mut Interpreter, args: &[Value]) -> Result<Value, RuntimeError> {
         let saved_env = interpreter.env.clone();
-        let saved_retval = interpreter.retval.clone();
         let saved_enclosing_function = interpreter.enclosing_function;
-
-        let mut env = self.closure.clone();
-        env.venv.extend(saved_env.venv.clone());
-        env.venv.extend(args_env);
-
-        if let Some(this_val) = &self.this_binding {
-            // this is just used for lookup on name. source location is meaningless and unused`
-            let this_symbol = Interpreter::this_symbol(0, -1);
-            env.venv.insert(
-                this_symbol.name,
-                (
-                    Some(*this_val.clone()),
-                    SourceLocation {
-                        line: this_symbol.line,
-                        col: this_symbol.col,
-                    },
-                ),
-            );
-        } else if let Ok(this_val) = interpreter.lookup(&Interpreter::this_symbol(0, -1)) {
-            // this is just used for lookup on name. source location is meaningless and unused`
-            let this_symbol = Interpreter::this_symbol(0, -1);
-            env.venv.insert(
-                this_symbol.name,
-                (
-                    Some(this_val.clone()),
-                    SourceLocation {
-                        line: this_symbol.line,
-                        col: this_symbol.col,
-                    },
-                ),
-            );
+        let saved_locals = std::mem::replace(&mut interpreter.locals, self.locals.clone());
+        let saved_module = std::mem::replace(&mut interpreter.current_module, self.module);
+
+        let env = match &self.this_binding {
+            Some(this_val) => {
+                let this_env = Environment::with_enclosing(&self.closure);
+                // this is just used for lookup on name. source location is meaningless and unused`
+                this_env.define(resolver::this_symbol(0, -1), Some(*this_val.clone()));
+                Environment::with_enclosing(&this_env)
+            }
+            None => Environment::with_enclosing(&self.closure),
+        };
+        for (param, arg) in self.parameters.iter().zip(args.iter()) {
+            env.define(param.clone(), Some(arg.clone()));
         }
-        let env = env;
 
         interpreter.env = env;
         interpreter.enclosing_function = Some(self.id);
-        interpreter.backtrace.push((0, self.name.name.clone()));
-        interpreter.interpret(&self.body)?;
+        interpreter.backtrace.push(BacktraceFrame {
+            name: Some(self.name.name.clone()),
+            declared_at: Some(self.name.location()),
+            call_site: interpreter.call_site.take(),
+            module: self.module,
+        });
+        let res = interpreter.execute_stmts(&self.body);
 
-        let retval = interpreter.retval.clone();
-        interpreter.backtrace.pop();
         interpreter.enclosing_function = saved_enclosing_function;
         interpreter.env = saved_env;
-        interpreter.retval = saved_retval;
-
-        match retval {
-            Some(val) => {
-                let val_type = type_of(&val);
-                if self.is_initializer && val_type != Type::Nil {
-                    Err(format!(
-                        "TypeError: init should only return nil (perhaps implicitly), not {:?}",
-                        val_type
-                    ))
-                } else {
-                    Ok(val)
-                }
-            }
-            None => {
-                if self.is_initializer {
-                    match &self.this_binding {
-                        Some(this_val) => Ok(*this_val.clone()),
-                        None => {
-                            panic!("Internal intepreter error: could not find binding for this.")
-                        }
-                    }
-                } else {
-                    Ok(Value::Nil)
-                }
+        interpreter.locals = saved_locals;
+        interpreter.current_module = saved_module;
+
+        let retval = match res {
+            Ok(()) => Value::Nil,
+            Err(Unwind::Return(val)) => val,
+            Err(Unwind::Throw(err)) => return Err(err),
+            Err(Unwind::Break) | Err(Unwind::Continue) => panic!(
+                "Internal interpreter error: loop control escaped function {}.",
+                self.name.name
+            ),
+        };
+        interpreter.backtrace.pop();
+
+        if !self.is_initializer {
+            return Ok(retval);
+        }
+
+        match (retval, &self.this_binding) {
+            (Value::Nil, Some(this_val)) => Ok(*this_val.clone()),
+            (Value::Nil, None) => {
+                panic!("Internal intepreter error: could not find binding for this.")
             }
+            (val, _) => Err(RuntimeError::InitReturnsNonNil {
+                ty: type_of(&val),
+                loc: self.name.location(),
+            }),
         }
     }
 }
@@ -164,7 +153,7 @@ impl Callable for LoxClass {
             None => 0,
         }
     }
-    fn call(&self, interpreter: &mut Interpreter, args: &[Value]) -> Result<Value, String> {
+    fn call(&self, interpreter: &mut Interpreter, args: &[Value]) -> Result<Value, RuntimeError> {
         let instance = interpreter.create_instance(&self.name, self.id);
 
         if let Some(mut initializer) = self.init(interpreter) {
@@ -209,12 +198,16 @@ pub struct LoxInstance {
 }
 
 impl LoxInstance {
-    fn getattr(&self, attr: &str, interpreter: &Interpreter) -> Result<Value, String> {
-        match self.fields.get(attr) {
+    fn getattr(
+        &self,
+        attr: &expr::Symbol,
+        interpreter: &Interpreter,
+    ) -> Result<Value, RuntimeError> {
+        match self.fields.get(&attr.name) {
             Some(val) => Ok(val.clone()),
             None => {
                 let cls = interpreter.get_lox_class(self.class_id);
-                if let Some((func_name, method_id)) = cls.find_method(attr, interpreter) {
+                if let Some((func_name, method_id)) = cls.find_method(&attr.name, interpreter) {
                     return Ok(Value::LoxFunction(
                         func_name,
                         method_id,
@@ -224,10 +217,11 @@ impl LoxInstance {
                         ))),
                     ));
                 }
-                Err(format!(
-                    "AttributeError: '{}' instance has no '{}' attribute.",
-                    self.class_name.name, attr
-                ))
+                Err(RuntimeError::NoSuchAttribute {
+                    class_name: self.class_name.name.clone(),
+                    attr: attr.name.clone(),
+                    loc: attr.location(),
+                })
             }
         }
     }
@@ -248,6 +242,61 @@ pub enum Value {
     LoxClass(expr::Symbol, /*id*/ u64),
     LoxInstance(expr::Symbol, /*id*/ u64),
     List(/*id*/ u64),
+    Dict(/*id*/ u64),
+    Module(/*id*/ u64),
+}
+
+/// A dict key. Numbers are compared by value, so `1` and `1.0` are the same key.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum DictKey {
+    Number(/*bits*/ u64),
+    String(String),
+}
+
+impl DictKey {
+    pub fn from_value(val: &Value) -> Option<DictKey> {
+        match val {
+            // -0.0 and 0.0 are equal but have different bits
+            Value::Number(n) if *n == 0.0 => Some(DictKey::Number(0f64.to_bits())),
+            Value::Number(n) => Some(DictKey::Number(n.to_bits())),
+            Value::String(s) => Some(DictKey::String(s.clone())),
+            _ => None,
+        }
+    }
+
+    pub fn to_value(&self) -> Value {
+        match self {
+            DictKey::Number(bits) => Value::Number(f64::from_bits(*bits)),
+            DictKey::String(s) => Value::String(s.clone()),
+        }
+    }
+}
+
+/// A hash map that remembers insertion order, so that dicts print the same way every time.
+#[derive(Debug, Clone, Default)]
+pub struct Dict {
+    indices: HashMap<DictKey, usize>,
+    entries: Vec<(DictKey, Value)>,
+}
+
+impl Dict {
+    pub fn get(&self, key: &DictKey) -> Option<&Value> {
+        self.indices.get(key).map(|idx| &self.entries[*idx].1)
+    }
+
+    pub fn insert(&mut self, key: DictKey, val: Value) {
+        match self.indices.get(&key) {
+            Some(idx) => self.entries[*idx].1 = val,
+            None => {
+                self.indices.insert(key.clone(), self.entries.len());
+                self.entries.push((key, val));
+            }
+        }
+    }
+
+    pub fn entries(&self) -> &[(DictKey, Value)] {
+        &self.entries
+    }
 }
 
 fn as_callable(interpreter: &Interpreter, value: &Value) -> Option<Box<dyn Callable>> {
@@ -275,6 +324,21 @@ pub enum Type {
     LoxClass,
     LoxInstance,
     List,
+    Dict,
+    Module,
+}
+
+/// Names the interpreter itself gives meaning to: its globals, the initializer method and
+/// the fields of caught errors. Renaming these changes what a program does.
+pub fn builtin_names() -> Vec<String> {
+    let interpreter: Interpreter = Default::default();
+    let mut names = interpreter.globals.names();
+    names.extend(
+        [INIT, ERROR_MESSAGE, ERROR_LINE, ERROR_COL, ERROR_VALUE]
+            .iter()
+            .map(|name| String::from(*name)),
+    );
+    names
 }
 
 pub fn type_of(val: &Value) -> Type {
@@ -288,53 +352,339 @@ pub fn type_of(val: &Value) -> Type {
         Value::LoxClass(_, _) => Type::LoxClass,
         Value::LoxInstance(_, _) => Type::LoxInstance,
         Value::List(_) => Type::List,
+        Value::Dict(_) => Type::Dict,
+        Value::Module(_) => Type::Module,
     }
 }
 
+/// Everything that can go wrong while running a program. Errors raised by native
+/// functions don't know where they were called from; the call fills that in.
 #[derive(Debug, Clone)]
-pub struct SourceLocation {
-    line: usize,
-    col: i64,
+pub enum RuntimeError {
+    Interrupted,
+    // a value raised by फाल; `description` is how it prints
+    Thrown {
+        value: Value,
+        description: String,
+        loc: SourceLocation,
+    },
+    // `len` is the length of the path literal, for diagnostics
+    ImportFailed {
+        path: String,
+        reason: String,
+        loc: SourceLocation,
+        len: usize,
+    },
+    ImportCycle {
+        cycle: String,
+        loc: SourceLocation,
+        len: usize,
+    },
+    NoSuchModuleMember {
+        module: String,
+        name: String,
+        loc: SourceLocation,
+    },
+    UndefinedVariable {
+        name: String,
+        loc: SourceLocation,
+    },
+    UndefinedButDeclared {
+        name: String,
+        loc: SourceLocation,
+        declared_at: SourceLocation,
+    },
+    AssignToUndeclared {
+        name: String,
+        loc: SourceLocation,
+    },
+    InitReturnsNonNil {
+        ty: Type,
+        loc: SourceLocation,
+    },
+    NoSuchAttribute {
+        class_name: String,
+        attr: String,
+        loc: SourceLocation,
+    },
+    NoAttributes {
+        ty: Type,
+        loc: SourceLocation,
+    },
+    ClassInheritsFromItself {
+        name: String,
+        loc: SourceLocation,
+    },
+    SuperclassNotClass {
+        ty: Type,
+        loc: SourceLocation,
+    },
+    NoSuperMethod {
+        name: String,
+        loc: SourceLocation,
+    },
+    SuperOutsideMethod {
+        loc: SourceLocation,
+    },
+    NotCallable {
+        ty: Type,
+        loc: SourceLocation,
+    },
+    ArityMismatch {
+        arity: u8,
+        num_args: usize,
+        loc: SourceLocation,
+    },
+    DivisionByZero {
+        loc: SourceLocation,
+    },
+    InvalidBinaryOperands {
+        op: expr::BinaryOpTy,
+        lhs: Type,
+        rhs: Type,
+        loc: SourceLocation,
+    },
+    InvalidUnaryOperand {
+        op: expr::UnaryOpTy,
+        ty: Type,
+        loc: SourceLocation,
+    },
+    NotSubscriptable {
+        ty: Type,
+        loc: SourceLocation,
+    },
+    IndexNotNumber {
+        ty: Type,
+        loc: SourceLocation,
+    },
+    IndexNotInteger {
+        index: f64,
+        loc: SourceLocation,
+    },
+    IndexOutOfRange {
+        index: f64,
+        len: usize,
+        loc: SourceLocation,
+    },
+    InvalidKey {
+        ty: Type,
+        loc: SourceLocation,
+    },
+    KeyNotFound {
+        key: String,
+        loc: SourceLocation,
+    },
+    NoLen {
+        ty: Type,
+        loc: Option<SourceLocation>,
+    },
+    InvalidArgument {
+        function: &'static str,
+        position: usize,
+        ty: Type,
+        loc: Option<SourceLocation>,
+    },
+    ArgumentNotCallable {
+        function: &'static str,
+        position: usize,
+        ty: Type,
+        loc: Option<SourceLocation>,
+    },
 }
 
-#[derive(Debug, Default, Clone)]
-pub struct Environment {
-    enclosing: Option<Box<Environment>>,
+impl RuntimeError {
+    /// Gives an error from a native function the location of the call that raised it.
+    fn at_call_site(mut self, call_site: SourceLocation) -> RuntimeError {
+        if let RuntimeError::NoLen { loc, .. }
+        | RuntimeError::InvalidArgument { loc, .. }
+        | RuntimeError::ArgumentNotCallable { loc, .. } = &mut self
+        {
+            loc.get_or_insert(call_site);
+        }
+        self
+    }
+
+    pub fn location(&self) -> Option<SourceLocation> {
+        match self {
+            RuntimeError::Interrupted => None,
+            RuntimeError::NoLen { loc, .. }
+            | RuntimeError::InvalidArgument { loc, .. }
+            | RuntimeError::ArgumentNotCallable { loc, .. } => *loc,
+            RuntimeError::Thrown { loc, .. }
+            | RuntimeError::ImportFailed { loc, .. }
+            | RuntimeError::ImportCycle { loc, .. }
+            | RuntimeError::NoSuchModuleMember { loc, .. }
+            | RuntimeError::UndefinedVariable { loc, .. }
+            | RuntimeError::UndefinedButDeclared { loc, .. }
+            | RuntimeError::AssignToUndeclared { loc, .. }
+            | RuntimeError::InitReturnsNonNil { loc, .. }
+            | RuntimeError::NoSuchAttribute { loc, .. }
+            | RuntimeError::NoAttributes { loc, .. }
+            | RuntimeError::ClassInheritsFromItself { loc, .. }
+            | RuntimeError::SuperclassNotClass { loc, .. }
+            | RuntimeError::NoSuperMethod { loc, .. }
+            | RuntimeError::SuperOutsideMethod { loc }
+            | RuntimeError::NotCallable { loc, .. }
+            | RuntimeError::ArityMismatch { loc, .. }
+            | RuntimeError::DivisionByZero { loc }
+            | RuntimeError::InvalidBinaryOperands { loc, .. }
+            | RuntimeError::InvalidUnaryOperand { loc, .. }
+            | RuntimeError::NotSubscriptable { loc, .. }
+            | RuntimeError::IndexNotNumber { loc, .. }
+            | RuntimeError::IndexNotInteger { loc, .. }
+            | RuntimeError::IndexOutOfRange { loc, .. }
+            | RuntimeError::InvalidKey { loc, .. }
+            | RuntimeError::KeyNotFound { loc, .. } => Some(*loc),
+        }
+    }
+}
+
+impl fmt::Display for RuntimeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use messages::Message;
+
+        let message = match self {
+            RuntimeError::Interrupted => Message::Interrupted,
+            RuntimeError::Thrown { description, .. } => Message::UncaughtThrow(description),
+            RuntimeError::ImportFailed { path, reason, .. } => {
+                Message::ImportFailed { path, reason }
+            }
+            RuntimeError::ImportCycle { cycle, .. } => Message::ImportCycle(cycle),
+            RuntimeError::NoSuchModuleMember { module, name, .. } => {
+                Message::NoSuchModuleMember { module, name }
+            }
+            RuntimeError::UndefinedVariable { name, .. } => Message::UndefinedVariable { name },
+            RuntimeError::UndefinedButDeclared {
+                name, declared_at, ..
+            } => Message::UndefinedButDeclared {
+                name,
+                decl_line: declared_at.line,
+                // 1-based, at the start of the name, like the rendered diagnostics
+                decl_col: declared_at.col - name.chars().count() as i64 + 2,
+            },
+            RuntimeError::AssignToUndeclared { name, .. } => Message::AssignToUndeclared { name },
+            RuntimeError::InitReturnsNonNil { ty, .. } => Message::InitReturnsNonNil(*ty),
+            RuntimeError::NoSuchAttribute {
+                class_name, attr, ..
+            } => Message::NoSuchAttribute { class_name, attr },
+            RuntimeError::NoAttributes { ty, .. } => Message::NoAttributes(*ty),
+            RuntimeError::ClassInheritsFromItself { name, .. } => {
+                Message::ClassInheritsFromItself { name }
+            }
+            RuntimeError::SuperclassNotClass { ty, .. } => Message::SuperclassNotClass(*ty),
+            RuntimeError::NoSuperMethod { name, .. } => Message::NoSuperMethod { name },
+            RuntimeError::SuperOutsideMethod { .. } => Message::SuperOutsideMethod,
+            RuntimeError::NotCallable { ty, .. } => Message::NotCallable { ty: *ty },
+            RuntimeError::ArityMismatch {
+                arity, num_args, ..
+            } => Message::ArityMismatch {
+                arity: *arity,
+                num_args: *num_args,
+            },
+            RuntimeError::DivisionByZero { .. } => Message::DivisionByZero,
+            RuntimeError::InvalidBinaryOperands { op, lhs, rhs, .. } => {
+                Message::InvalidBinaryOperands {
+                    op: *op,
+                    lhs: *lhs,
+                    rhs: *rhs,
+                }
+            }
+            RuntimeError::InvalidUnaryOperand { op, ty, .. } => {
+                Message::InvalidUnaryOperand { op: *op, ty: *ty }
+            }
+            RuntimeError::NotSubscriptable { ty, .. } => Message::NotSubscriptable { ty: *ty },
+            RuntimeError::IndexNotNumber { ty, .. } => Message::IndexNotNumber { ty: *ty },
+            RuntimeError::IndexNotInteger { index, .. } => {
+                Message::IndexNotInteger { index: *index }
+            }
+            RuntimeError::IndexOutOfRange { index, len, .. } => Message::IndexOutOfRange {
+                index: *index,
+                len: *len,
+            },
+            RuntimeError::InvalidKey { ty, .. } => Message::InvalidKey { ty: *ty },
+            RuntimeError::KeyNotFound { key, .. } => Message::KeyNotFound { key },
+            RuntimeError::NoLen { ty, .. } => Message::NoLen(*ty),
+            RuntimeError::InvalidArgument {
+                function,
+                position,
+                ty,
+                ..
+            } => Message::InvalidArgument {
+                function,
+                position: *position,
+                ty: *ty,
+            },
+            RuntimeError::ArgumentNotCallable {
+                function,
+                position,
+                ty,
+                ..
+            } => Message::ArgumentNotCallable {
+                function,
+                position: *position,
+                ty: *ty,
+            },
+        };
+        write!(f, "{}", message)
+    }
+}
+
+#[derive(Debug, Default)]
+struct Frame {
+    enclosing: Option<Environment>,
     // SourceLocation is the location of a declaration
     venv: HashMap<String, (Option<Value>, SourceLocation)>,
 }
 
-pub enum LookupResult<'a> {
-    Ok(&'a Value),
+/// A handle to a scope. Cloning an `Environment` shares the underlying frame, so closures
+/// and the scope that defined them observe each other's assignments.
+#[derive(Debug, Default, Clone)]
+pub struct Environment {
+    frame: Rc<RefCell<Frame>>,
+}
+
+pub enum LookupResult {
+    Ok(Value),
     UndefButDeclared(SourceLocation),
     UndefAndNotDeclared,
 }
 
 impl Environment {
-    pub fn with_enclosing(enclosing: Environment) -> Environment {
+    pub fn new(venv: HashMap<String, (Option<Value>, SourceLocation)>) -> Environment {
         Environment {
-            enclosing: Some(Box::new(enclosing)),
-            venv: HashMap::new(),
+            frame: Rc::new(RefCell::new(Frame {
+                enclosing: None,
+                venv,
+            })),
         }
     }
 
-    pub fn define(&mut self, sym: expr::Symbol, maybe_val: Option<Value>) {
-        self.venv.insert(
-            sym.name,
-            (
-                maybe_val,
-                SourceLocation {
-                    line: sym.line,
-                    col: sym.col,
-                },
-            ),
-        );
+    /// The names defined directly in this environment, not in its enclosing ones.
+    pub fn names(&self) -> Vec<String> {
+        self.frame.borrow().venv.keys().cloned().collect()
+    }
+
+    pub fn with_enclosing(enclosing: &Environment) -> Environment {
+        Environment {
+            frame: Rc::new(RefCell::new(Frame {
+                enclosing: Some(enclosing.clone()),
+                venv: HashMap::new(),
+            })),
+        }
+    }
+
+    pub fn define(&self, sym: expr::Symbol, maybe_val: Option<Value>) {
+        let loc = sym.location();
+        self.frame
+            .borrow_mut()
+            .venv
+            .insert(sym.name, (maybe_val, loc));
     }
 
     pub fn lookup(&self, sym: &expr::Symbol) -> LookupResult {
-        match self.venv.get(&sym.name) {
+        match self.frame.borrow().venv.get(&sym.name) {
             Some((maybe_val, defn_source_location)) => match maybe_val {
-                Some(val) => LookupResult::Ok(val),
+                Some(val) => LookupResult::Ok(val.clone()),
                 None => LookupResult::UndefButDeclared(SourceLocation {
                     line: defn_source_location.line,
                     col: defn_source_location.col,
@@ -344,41 +694,100 @@ impl Environment {
         }
     }
 
-    pub fn get(&self, sym: &expr::Symbol) -> Result<&Value, String> {
+    pub fn get(&self, sym: &expr::Symbol) -> Result<Value, RuntimeError> {
         match self.lookup(sym) {
             LookupResult::Ok(val) => Ok(val),
-            LookupResult::UndefButDeclared(source_location) => Err(format!(
-                "Use of undefined variable '{}' at line={},col={}.\
-                \nNote: {} was previously declared at line={},col={}, \
-                but was never defined.",
-                &sym.name, sym.line, sym.col, &sym.name, source_location.line, source_location.col
-            )),
-            LookupResult::UndefAndNotDeclared => match &self.enclosing {
+            LookupResult::UndefButDeclared(source_location) => {
+                Err(RuntimeError::UndefinedButDeclared {
+                    name: sym.name.clone(),
+                    loc: sym.location(),
+                    declared_at: source_location,
+                })
+            }
+            LookupResult::UndefAndNotDeclared => match &self.frame.borrow().enclosing {
                 Some(enclosing) => enclosing.get(sym),
-                None => Err(format!(
-                    "Use of undefined variable {} at line={},col={}.\nNote: {} was never declared.",
-                    &sym.name, sym.line, sym.col, &sym.name
-                )),
+                None => Err(RuntimeError::UndefinedVariable {
+                    name: sym.name.clone(),
+                    loc: sym.location(),
+                }),
             },
         }
     }
 
-    pub fn assign(&mut self, sym: expr::Symbol, val: &Value) -> Result<(), String> {
-        if self.venv.contains_key(&sym.name) {
+    fn ancestor(&self, depth: usize) -> Environment {
+        let mut env = self.clone();
+        for _ in 0..depth {
+            let enclosing = match &env.frame.borrow().enclosing {
+                Some(enclosing) => enclosing.clone(),
+                None => panic!(
+                    "Internal interpreter error: no enclosing environment at depth {}.",
+                    depth
+                ),
+            };
+            env = enclosing;
+        }
+        env
+    }
+
+    pub fn get_at(&self, depth: usize, sym: &expr::Symbol) -> Result<Value, RuntimeError> {
+        self.ancestor(depth).get(sym)
+    }
+
+    pub fn assign_at(&self, depth: usize, sym: expr::Symbol, val: &Value) -> Result<(), RuntimeError> {
+        self.ancestor(depth).assign(sym, val)
+    }
+
+    pub fn assign(&self, sym: expr::Symbol, val: &Value) -> Result<(), RuntimeError> {
+        if self.frame.borrow().venv.contains_key(&sym.name) {
             self.define(sym, Some(val.clone()));
             return Ok(());
         }
 
-        match &mut self.enclosing {
+        match &self.frame.borrow().enclosing {
             Some(enclosing) => enclosing.assign(sym, val),
-            None => Err(format!(
-                "attempting to assign to undeclared variable at line={},col={}",
-                sym.line, sym.col
-            )),
+            None => Err(RuntimeError::AssignToUndeclared {
+                name: sym.name.clone(),
+                loc: sym.location(),
+            }),
         }
     }
 }
 
+/// Why a statement stopped before reaching its end. Each variant unwinds through
+/// enclosing statements (and, for Return and Throw, calls) until something handles it.
+#[derive(Debug)]
+enum Unwind {
+    Return(Value),
+    Break,
+    Continue,
+    Throw(RuntimeError),
+}
+
+impl From<RuntimeError> for Unwind {
+    fn from(err: RuntimeError) -> Unwind {
+        Unwind::Throw(err)
+    }
+}
+
+/// One active call. `call_site` is where the caller was executing when it made the call,
+/// so the innermost frame's current line comes from the error itself.
+#[derive(Debug, Clone)]
+pub struct BacktraceFrame {
+    // None for the top level of the script or of a module
+    pub name: Option<String>,
+    pub declared_at: Option<SourceLocation>,
+    pub call_site: Option<SourceLocation>,
+    // the module the frame runs in; None for the main script
+    pub module: Option<u64>,
+}
+
+/// A file loaded with आयात. Its top-level definitions live in `namespace`.
+pub struct Module {
+    pub path: PathBuf,
+    pub input: input::Input,
+    pub namespace: Environment,
+}
+
 pub struct Interpreter {
     pub counter: u64,
     pub lambda_counter: u64,
@@ -386,13 +795,28 @@ pub struct Interpreter {
     pub lox_instances: HashMap<u64, LoxInstance>,
     pub lox_classes: HashMap<u64, LoxClass>,
     pub lists: HashMap<u64, Vec<Value>>,
+    pub dicts: HashMap<u64, Dict>,
     pub env: Environment,
     pub globals: Environment,
-    pub retval: Option<Value>,
+    pub locals: Rc<resolver::Locals>,
     pub output: Vec<String>,
     pub enclosing_function: Option<u64>,
     pub interrupted: Arc<AtomicBool>,
-    pub backtrace: Vec<(u64, String)>,
+    pub backtrace: Vec<BacktraceFrame>,
+    // location of the call being made, until the callee claims it for its frame
+    call_site: Option<SourceLocation>,
+    error_class: u64,
+    pub modules: HashMap<u64, Module>,
+    // canonical path of every module loaded so far
+    module_ids: HashMap<PathBuf, u64>,
+    // modules whose top level is still running, outermost first
+    importing: Vec<u64>,
+    current_module: Option<u64>,
+    /// The main script, which imports are resolved against. Imports from the REPL or
+    /// the command line are resolved against the working directory.
+    pub script_path: Option<PathBuf>,
+    /// The dialect from the command line, for modules without a pragma of their own.
+    pub dialect: Option<dialect::Dialect>,
 }
 
 impl Default for Interpreter {
@@ -429,7 +853,13 @@ impl Default for Interpreter {
                             let elts = interp.get_list_elts(*list_id);
                             Ok(Value::Number(elts.len() as f64))
                         }
-                        val => Err(format!("Object of type {:?} has no len.", type_of(val))),
+                        Value::Dict(dict_id) => {
+                            Ok(Value::Number(interp.get_dict(*dict_id).entries().len() as f64))
+                        }
+                        val => Err(RuntimeError::NoLen {
+                            ty: type_of(val),
+                            loc: None,
+                        }),
                     },
                 })),
                 SourceLocation {
@@ -451,14 +881,18 @@ impl Default for Interpreter {
                                 .collect();
                             Ok(interpreter.create_list(elts))
                         }
-                        (Value::Number(_), high) => Err(format!(
-                            "invalid high argument of type {:?} in iota expression.",
-                            type_of(high)
-                        )),
-                        (low, _) => Err(format!(
-                            "invalid low argument of type {:?} in iota expression.",
-                            type_of(low)
-                        )),
+                        (Value::Number(_), high) => Err(RuntimeError::InvalidArgument {
+                            function: "iota",
+                            position: 2,
+                            ty: type_of(high),
+                            loc: None,
+                        }),
+                        (low, _) => Err(RuntimeError::InvalidArgument {
+                            function: "iota",
+                            position: 1,
+                            ty: type_of(low),
+                            loc: None,
+                        }),
                     },
                 })),
                 SourceLocation {
@@ -485,16 +919,20 @@ impl Default for Interpreter {
                                     }
                                     Ok(Value::Nil)
                                 }
-                                None => Err(format!(
-                                    "The second argument to for_each must be callable. Found {:?}.",
-                                    type_of(&values[1])
-                                )),
+                                None => Err(RuntimeError::ArgumentNotCallable {
+                                    function: "forEach",
+                                    position: 2,
+                                    ty: type_of(&values[1]),
+                                    loc: None,
+                                }),
                             }
                         }
-                        val => Err(format!(
-                            "Can't call forEach on value of type {:?}.",
-                            type_of(val)
-                        )),
+                        val => Err(RuntimeError::InvalidArgument {
+                            function: "forEach",
+                            position: 1,
+                            ty: type_of(val),
+                            loc: None,
+                        }),
                     },
                 })),
                 SourceLocation {
@@ -521,16 +959,109 @@ impl Default for Interpreter {
                                     }
                                     Ok(interpreter.create_list(res_elts))
                                 }
-                                None => Err(format!(
-                                    "The second argument to for_each must be callable. Found {:?}.",
-                                    type_of(&values[1])
-                                )),
+                                None => Err(RuntimeError::ArgumentNotCallable {
+                                    function: "map",
+                                    position: 1,
+                                    ty: type_of(&values[0]),
+                                    loc: None,
+                                }),
                             }
                         }
-                        val => Err(format!(
-                            "Can't call forEach on value of type {:?}.",
-                            type_of(val)
-                        )),
+                        val => Err(RuntimeError::InvalidArgument {
+                            function: "map",
+                            position: 2,
+                            ty: type_of(val),
+                            loc: None,
+                        }),
+                    },
+                })),
+                SourceLocation {
+                    line: 1337,
+                    col: 1337,
+                },
+            ),
+        );
+        globals_venv.insert(
+            String::from("keys"),
+            (
+                Some(Value::NativeFunction(NativeFunction {
+                    name: String::from("keys"),
+                    arity: 1,
+                    callable: |interpreter, values| match &values[0] {
+                        Value::Dict(dict_id) => {
+                            let keys = interpreter
+                                .get_dict(*dict_id)
+                                .entries()
+                                .iter()
+                                .map(|(key, _)| key.to_value())
+                                .collect();
+                            Ok(interpreter.create_list(keys))
+                        }
+                        val => Err(RuntimeError::InvalidArgument {
+                            function: "keys",
+                            position: 1,
+                            ty: type_of(val),
+                            loc: None,
+                        }),
+                    },
+                })),
+                SourceLocation {
+                    line: 1337,
+                    col: 1337,
+                },
+            ),
+        );
+        globals_venv.insert(
+            String::from("values"),
+            (
+                Some(Value::NativeFunction(NativeFunction {
+                    name: String::from("values"),
+                    arity: 1,
+                    callable: |interpreter, values| match &values[0] {
+                        Value::Dict(dict_id) => {
+                            let vals = interpreter
+                                .get_dict(*dict_id)
+                                .entries()
+                                .iter()
+                                .map(|(_, val)| val.clone())
+                                .collect();
+                            Ok(interpreter.create_list(vals))
+                        }
+                        val => Err(RuntimeError::InvalidArgument {
+                            function: "values",
+                            position: 1,
+                            ty: type_of(val),
+                            loc: None,
+                        }),
+                    },
+                })),
+                SourceLocation {
+                    line: 1337,
+                    col: 1337,
+                },
+            ),
+        );
+        globals_venv.insert(
+            String::from("has"),
+            (
+                Some(Value::NativeFunction(NativeFunction {
+                    name: String::from("has"),
+                    arity: 2,
+                    callable: |interpreter, values| match &values[0] {
+                        Value::Dict(dict_id) => {
+                            // values that can't be keys are in no dict
+                            let found = match DictKey::from_value(&values[1]) {
+                                Some(key) => interpreter.get_dict(*dict_id).get(&key).is_some(),
+                                None => false,
+                            };
+                            Ok(Value::Bool(found))
+                        }
+                        val => Err(RuntimeError::InvalidArgument {
+                            function: "has",
+                            position: 1,
+                            ty: type_of(val),
+                            loc: None,
+                        }),
                     },
                 })),
                 SourceLocation {
@@ -540,38 +1071,75 @@ impl Default for Interpreter {
             ),
         );
 
-        let globals = Environment {
-            enclosing: None,
-            venv: globals_venv,
-        };
+        let globals = Environment::new(globals_venv);
 
-        Interpreter {
+        let mut interpreter = Interpreter {
             counter: 0,
             lambda_counter: 0,
             lox_functions: Default::default(),
             lox_instances: Default::default(),
             lox_classes: Default::default(),
             lists: Default::default(),
-            env: Default::default(),
+            dicts: Default::default(),
+            env: globals.clone(),
             globals,
-            retval: None,
+            locals: Default::default(),
             output: Default::default(),
             enclosing_function: None,
             interrupted: Arc::new(AtomicBool::new(false)),
-            backtrace: vec![(0, "script".to_string())],
-        }
+            backtrace: vec![BacktraceFrame {
+                name: None,
+                declared_at: None,
+                call_site: None,
+                module: None,
+            }],
+            call_site: None,
+            error_class: 0,
+            modules: Default::default(),
+            module_ids: Default::default(),
+            importing: Vec::new(),
+            current_module: None,
+            script_path: None,
+            dialect: None,
+        };
+        interpreter.define_error_class();
+        interpreter
     }
 }
 
 impl Interpreter {
-    pub fn interpret(&mut self, stmts: &[expr::Stmt]) -> Result<(), String> {
+    pub fn interpret(&mut self, stmts: &[expr::Stmt]) -> Result<(), RuntimeError> {
+        self.interrupted.store(false, Ordering::Release);
+        self.call_site = None;
+        match self.execute_stmts(stmts) {
+            Ok(()) => Ok(()),
+            Err(Unwind::Throw(err)) => Err(err),
+            // the parser and resolver reject these outside of loops and functions
+            Err(unwind) => panic!(
+                "Internal interpreter error: {:?} at the top level.",
+                unwind
+            ),
+        }
+    }
+
+    /// Evaluates a single expression, e.g. so the REPL can echo its value.
+    pub fn interpret_expression(&mut self, expr: &expr::Expr) -> Result<Value, RuntimeError> {
         self.interrupted.store(false, Ordering::Release);
+        self.call_site = None;
+        self.interpret_expr(expr)
+    }
+
+    fn execute_stmts(&mut self, stmts: &[expr::Stmt]) -> Result<(), Unwind> {
         for stmt in stmts {
             self.execute(stmt)?
         }
         Ok(())
     }
 
+    pub fn resolve(&mut self, locals: resolver::Locals) {
+        Rc::make_mut(&mut self.locals).extend(locals);
+    }
+
     pub fn get_lox_function(&self, id: u64) -> &LoxFunction {
         match self.lox_functions.get(&id) {
             Some(func) => func,
@@ -602,13 +1170,62 @@ impl Interpreter {
         }
     }
 
-    pub fn format_backtrace(&self) -> String {
+    /// The source of the module a runtime error was raised in, when that's not the main
+    /// script. Frames are only popped on success, so the innermost one is where it failed.
+    pub fn error_input(&self) -> Option<&input::Input> {
+        self.backtrace
+            .last()
+            .and_then(|frame| frame.module)
+            .map(|id| &self.get_module(id).input)
+    }
+
+    fn get_module(&self, id: u64) -> &Module {
+        match self.modules.get(&id) {
+            Some(module) => module,
+            None => panic!(
+                "Internal interpreter error: could not find module with id {}.",
+                id
+            ),
+        }
+    }
+
+    fn module_name(&self, id: u64) -> String {
+        diagnostics::source_name(&self.get_module(id).input.source)
+    }
+
+    pub fn format_backtrace(&self, err: &RuntimeError) -> String {
+        // an interrupt carries no location; the call in progress is the best we can do
+        let error_location = err.location().or(self.call_site);
         let lines: Vec<_> = self
             .backtrace
             .iter()
-            .map(|(_, funname)| format!("[line ??] in {}", funname))
+            .enumerate()
+            .map(|(i, frame)| {
+                let current = match self.backtrace.get(i + 1) {
+                    Some(callee) => callee.call_site,
+                    None => error_location,
+                };
+                let name = match &frame.name {
+                    Some(name) => name.clone(),
+                    None => messages::Message::ScriptFrame.to_string(),
+                };
+                let name = match frame.module {
+                    Some(id) => format!("{} ({})", name, self.module_name(id)),
+                    None => name,
+                };
+                messages::Message::BacktraceFrame {
+                    name: &name,
+                    line: current.map(|loc| loc.line),
+                    declared_at: frame.declared_at.map(|loc| loc.line),
+                }
+                .to_string()
+            })
             .collect();
-        format!("Backtrace (most recent call last):\n\n{}", lines.join("\n"))
+        format!(
+            "{}\n\n{}",
+            messages::Message::BacktraceHeader,
+            lines.join("\n")
+        )
     }
 
     fn get_list_elts(&self, list_id: u64) -> &Vec<Value> {
@@ -629,12 +1246,109 @@ impl Interpreter {
         res
     }
 
+    fn alloc_lambda_name(&mut self, source_location: &SourceLocation) -> expr::Symbol {
+        let res = expr::Symbol {
+            name: format!("__lambda_{}", self.lambda_counter),
+            line: source_location.line,
+            col: source_location.col,
+        };
+        self.lambda_counter += 1;
+        res
+    }
+
     fn create_list(&mut self, elts: Vec<Value>) -> Value {
         let list_id = self.alloc_id();
         self.lists.insert(list_id, elts);
         Value::List(list_id)
     }
 
+    fn get_dict(&self, dict_id: u64) -> &Dict {
+        match self.dicts.get(&dict_id) {
+            Some(dict) => dict,
+            None => panic!(
+                "Internal interpreter error! Couldn't find dict with id {}.",
+                dict_id
+            ),
+        }
+    }
+
+    fn create_dict(&mut self, dict: Dict) -> Value {
+        let dict_id = self.alloc_id();
+        self.dicts.insert(dict_id, dict);
+        Value::Dict(dict_id)
+    }
+
+    fn dict_key(key: &Value, source_location: &SourceLocation) -> Result<DictKey, RuntimeError> {
+        DictKey::from_value(key).ok_or(RuntimeError::InvalidKey {
+            ty: type_of(key),
+            loc: *source_location,
+        })
+    }
+
+    fn define_error_class(&mut self) {
+        let class_id = self.alloc_id();
+        let name = expr::Symbol {
+            name: String::from(ERROR_CLASS),
+            line: 0,
+            col: -1,
+        };
+        self.lox_classes.insert(
+            class_id,
+            LoxClass {
+                name: name.clone(),
+                superclass: None,
+                id: class_id,
+                methods: HashMap::new(),
+            },
+        );
+        self.globals
+            .define(name.clone(), Some(Value::LoxClass(name, class_id)));
+        self.error_class = class_id;
+    }
+
+    /// The value a समात block sees: an error object with the message and location of the
+    /// error. A value thrown with फाल is kept in its मान field, unless it is an error
+    /// object already, like a caught error thrown again.
+    fn caught_value(&mut self, err: RuntimeError) -> Value {
+        if let RuntimeError::Thrown {
+            value: value @ Value::LoxInstance(_, id),
+            ..
+        } = &err
+        {
+            if self.get_lox_instance(*id).class_id == self.error_class {
+                return value.clone();
+            }
+        }
+
+        let location = err.location().or(self.call_site.take());
+        let (message, thrown) = match err {
+            RuntimeError::Thrown {
+                value: Value::String(s),
+                ..
+            } => (s.clone(), Value::String(s)),
+            RuntimeError::Thrown {
+                value, description, ..
+            } => (description, value),
+            err => (err.to_string(), Value::Nil),
+        };
+        let class_name = self.get_lox_class(self.error_class).name.clone();
+        let instance = self.create_instance(&class_name, self.error_class);
+        if let Value::LoxInstance(_, id) = instance {
+            if let Some(inst) = self.lox_instances.get_mut(&id) {
+                let (line, col) = match location {
+                    Some(loc) => (Value::Number(loc.line as f64), Value::Number(loc.col as f64)),
+                    None => (Value::Nil, Value::Nil),
+                };
+                inst.fields
+                    .insert(String::from(ERROR_MESSAGE), Value::String(message));
+                inst.fields.insert(String::from(ERROR_LINE), line);
+                inst.fields.insert(String::from(ERROR_COL), col);
+                inst.fields.insert(String::from(ERROR_VALUE), thrown);
+            }
+        }
+        instance
+    }
+
     fn create_instance(&mut self, class_name: &expr::Symbol, class_id: u64) -> Value {
         let inst_id = self.alloc_id();
         let inst = LoxInstance {
@@ -647,20 +1361,17 @@ impl Interpreter {
         Value::LoxInstance(class_name.clone(), inst_id)
     }
 
-    fn execute(&mut self, stmt: &expr::Stmt) -> Result<(), String> {
-        if self.retval.is_some() {
-            return Ok(());
-        }
-
+    fn execute(&mut self, stmt: &expr::Stmt) -> Result<(), Unwind> {
         match stmt {
-            expr::Stmt::Expr(e) => match self.interpret_expr(e) {
-                Ok(_) => Ok(()),
-                Err(err) => Err(err),
-            },
+            expr::Stmt::Expr(e) => {
+                self.interpret_expr(e)?;
+                Ok(())
+            }
             expr::Stmt::ClassDecl(expr::ClassDecl {
                 name: sym,
                 superclass: maybe_superclass,
                 methods: stmt_methods,
+                ..
             }) => {
                 let class_id = self.alloc_id();
                 self.env
@@ -668,10 +1379,10 @@ impl Interpreter {
 
                 let superclass_id = if let Some(superclass_var) = maybe_superclass {
                     if superclass_var.name == sym.name {
-                        return Err(format!(
-                            "A class cannot inerit from itself (line={}, col={})",
-                            sym.line, sym.col
-                        ));
+                        return Err(Unwind::Throw(RuntimeError::ClassInheritsFromItself {
+                            name: sym.name.clone(),
+                            loc: sym.location(),
+                        }));
                     }
 
                     let superclass_val =
@@ -679,10 +1390,10 @@ impl Interpreter {
                     if let Value::LoxClass(_, id) = superclass_val {
                         Some(id)
                     } else {
-                        return Err(format!(
-                            "Only classes should appear as superclasses. Found {:?}.",
-                            type_of(&superclass_val)
-                        ));
+                        return Err(Unwind::Throw(RuntimeError::SuperclassNotClass {
+                            ty: type_of(&superclass_val),
+                            loc: superclass_var.location(),
+                        }));
                     }
                 } else {
                     None
@@ -705,6 +1416,8 @@ impl Interpreter {
                         this_binding: None,
                         superclass: superclass_id,
                         is_initializer,
+                        locals: self.locals.clone(),
+                        module: self.current_module,
                     };
 
                     self.lox_functions.insert(func_id, lox_function);
@@ -724,6 +1437,7 @@ impl Interpreter {
                 name,
                 params: parameters,
                 body,
+                ..
             }) => {
                 let func_id = self.alloc_id();
                 self.env.define(
@@ -740,6 +1454,8 @@ impl Interpreter {
                     this_binding: None,
                     superclass: None,
                     is_initializer: false,
+                    locals: self.locals.clone(),
+                    module: self.current_module,
                 };
 
                 self.lox_functions.insert(func_id, lox_function);
@@ -755,14 +1471,12 @@ impl Interpreter {
                 }
                 Ok(())
             }
-            expr::Stmt::Print(e) => match self.interpret_expr(e) {
-                Ok(val) => {
-                    println!("{}", self.format_val(&val));
-                    self.output.push(self.format_val(&val));
-                    Ok(())
-                }
-                Err(err) => Err(err),
-            },
+            expr::Stmt::Print(e) => {
+                let val = self.interpret_expr(e)?;
+                println!("{}", self.format_val(&val));
+                self.output.push(self.format_val(&val));
+                Ok(())
+            }
             expr::Stmt::VarDecl(sym, maybe_expr) => {
                 let maybe_val = match maybe_expr {
                     Some(expr) => Some(self.interpret_expr(expr)?),
@@ -771,82 +1485,299 @@ impl Interpreter {
                 self.env.define(sym.clone(), maybe_val);
                 Ok(())
             }
-            expr::Stmt::Block(stmts) => {
-                self.env = Environment::with_enclosing(self.env.clone());
-
-                for stmt in stmts.iter() {
-                    self.execute(stmt)?;
+            expr::Stmt::Block(stmts) => self.execute_block(stmts),
+            expr::Stmt::While(cond, body, maybe_increment) => {
+                while Interpreter::is_truthy(&self.interpret_expr(cond)?) {
+                    match self.execute(body) {
+                        Ok(()) | Err(Unwind::Continue) => {}
+                        Err(Unwind::Break) => break,
+                        Err(unwind) => return Err(unwind),
+                    }
+                    if let Some(increment) = maybe_increment {
+                        self.interpret_expr(increment)?;
+                    }
+                }
+                Ok(())
+            }
+            expr::Stmt::Throw(loc, value) => {
+                let value = self.interpret_expr(value)?;
+                Err(Unwind::Throw(RuntimeError::Thrown {
+                    description: self.format_val(&value),
+                    value,
+                    loc: *loc,
+                }))
+            }
+            expr::Stmt::Try {
+                body,
+                catch,
+                finally,
+            } => {
+                let backtrace_depth = self.backtrace.len();
+                let mut res = self.execute_block(body);
+
+                if let Some(catch) = catch {
+                    res = match res {
+                        // Ctrl-C stops the program no matter what
+                        Err(Unwind::Throw(err)) if !matches!(err, RuntimeError::Interrupted) => {
+                            self.backtrace.truncate(backtrace_depth);
+                            let caught = self.caught_value(err);
+                            let saved_env = self.env.clone();
+                            self.env = Environment::with_enclosing(&saved_env);
+                            self.env.define(catch.name.clone(), Some(caught));
+                            let res = self.execute_stmts(&catch.body);
+                            self.env = saved_env;
+                            res
+                        }
+                        res => res,
+                    };
                 }
 
-                if let Some(enclosing) = self.env.enclosing.clone() {
-                    self.env = *enclosing
-                } else {
-                    // TODO: how to do this without a runtime check?
-                    panic!("impossible");
+                if let Some(finally) = finally {
+                    // frames of an error still propagating, kept out of the way of any
+                    // calls made by the finally block
+                    let unwound_frames = self.backtrace.split_off(backtrace_depth);
+                    // leaving the finally block early overrides whatever was in flight
+                    self.execute_block(finally)?;
+                    self.backtrace.extend(unwound_frames);
                 }
 
-                Ok(())
+                res
             }
-            expr::Stmt::While(cond, body) => {
-                while Interpreter::is_truthy(&self.interpret_expr(cond)?) {
-                    self.execute(body)?;
-                }
+            expr::Stmt::Import {
+                name,
+                path,
+                source_location,
+                path_len,
+            } => {
+                let module = self.import(path, source_location, *path_len)?;
+                self.env.define(name.clone(), Some(module));
                 Ok(())
             }
+            expr::Stmt::Break => Err(Unwind::Break),
+            expr::Stmt::Continue => Err(Unwind::Continue),
             expr::Stmt::Return(_, maybe_res) => {
-                self.retval = Some(if let Some(res) = maybe_res {
-                    self.interpret_expr(res)?
-                } else {
-                    Value::Nil
-                });
-                Ok(())
+                let retval = match maybe_res {
+                    Some(res) => self.interpret_expr(res)?,
+                    None => Value::Nil,
+                };
+                Err(Unwind::Return(retval))
             }
         }
     }
 
-    fn lookup(&self, sym: &expr::Symbol) -> Result<&Value, String> {
-        match self.env.get(sym) {
-            Ok(val) => Ok(val),
-            Err(_) => self.globals.get(sym),
+    fn execute_block(&mut self, stmts: &[expr::Stmt]) -> Result<(), Unwind> {
+        let saved_env = self.env.clone();
+        self.env = Environment::with_enclosing(&saved_env);
+
+        let res = self.execute_stmts(stmts);
+
+        self.env = saved_env;
+
+        res
+    }
+
+    fn lookup(&self, sym: &expr::Symbol) -> Result<Value, RuntimeError> {
+        match self.locals.get(sym) {
+            Some(depth) => self.env.get_at(*depth, sym),
+            None => self.file_globals().get(sym),
+        }
+    }
+
+    fn assign(&self, sym: &expr::Symbol, val: &Value) -> Result<(), RuntimeError> {
+        match self.locals.get(sym) {
+            Some(depth) => self.env.assign_at(*depth, sym.clone(), val),
+            None => self.file_globals().assign(sym.clone(), val),
         }
     }
 
-    fn this_symbol(line: usize, col: i64) -> expr::Symbol {
-        expr::Symbol {
-            name: String::from("this"),
-            line,
-            col,
+    // the top-level scope of the file being run; a module's encloses the real globals
+    fn file_globals(&self) -> &Environment {
+        match self.current_module {
+            Some(id) => &self.get_module(id).namespace,
+            None => &self.globals,
         }
     }
 
-    fn interpret_expr(&mut self, expr: &expr::Expr) -> Result<Value, String> {
+    fn import(
+        &mut self,
+        path: &str,
+        loc: &SourceLocation,
+        len: usize,
+    ) -> Result<Value, RuntimeError> {
+        let base_dir = match self.current_module {
+            Some(id) => self.get_module(id).path.parent(),
+            None => self.script_path.as_deref().and_then(Path::parent),
+        };
+        let full_path = base_dir.unwrap_or_else(|| Path::new("")).join(path);
+        let import_failed = |reason: String| RuntimeError::ImportFailed {
+            path: full_path.display().to_string(),
+            reason,
+            loc: *loc,
+            len,
+        };
+
+        let key = fs::canonicalize(&full_path).map_err(|err| import_failed(err.to_string()))?;
+
+        // the main script is never a module, but importing it back is still a cycle
+        let script_key = self.script_path.as_ref().and_then(|path| fs::canonicalize(path).ok());
+        if script_key.as_ref() == Some(&key) {
+            let script_name = self
+                .script_path
+                .as_ref()
+                .map(|path| path.display().to_string())
+                .unwrap_or_default();
+            let mut cycle = vec![script_name.clone()];
+            cycle.extend(self.importing.iter().map(|id| self.module_name(*id)));
+            cycle.push(script_name);
+            return Err(RuntimeError::ImportCycle {
+                cycle: cycle.join(" -> "),
+                loc: *loc,
+                len,
+            });
+        }
+
+        if let Some(id) = self.module_ids.get(&key) {
+            if let Some(pos) = self.importing.iter().position(|importing| importing == id) {
+                let mut cycle: Vec<_> = self.importing[pos..]
+                    .iter()
+                    .map(|id| self.module_name(*id))
+                    .collect();
+                cycle.push(self.module_name(*id));
+                return Err(RuntimeError::ImportCycle {
+                    cycle: cycle.join(" -> "),
+                    loc: *loc,
+                    len,
+                });
+            }
+            return Ok(Value::Module(*id));
+        }
+
+        let content = fs::read_to_string(&key).map_err(|err| import_failed(err.to_string()))?;
+        let input = input::Input {
+            source: input::Source::File(full_path.display().to_string()),
+            content,
+        };
+        let module_dialect = dialect::for_source(
+            &input.content,
+            full_path.parent().unwrap_or_else(|| Path::new("")),
+            self.dialect.as_ref(),
+        )
+        .map_err(|err| import_failed(err.to_string()))?;
+        let (stmts, locals) =
+            Interpreter::load_module(&input, module_dialect.as_ref()).map_err(import_failed)?;
+
+        let id = self.alloc_id();
+        let namespace = Environment::with_enclosing(&self.globals);
+        self.modules.insert(
+            id,
+            Module {
+                path: full_path,
+                input,
+                namespace: namespace.clone(),
+            },
+        );
+        self.module_ids.insert(key.clone(), id);
+        self.importing.push(id);
+
+        let saved_env = std::mem::replace(&mut self.env, namespace);
+        let saved_locals = std::mem::replace(&mut self.locals, Rc::new(locals));
+        let saved_module = self.current_module.replace(id);
+        self.backtrace.push(BacktraceFrame {
+            name: None,
+            declared_at: None,
+            call_site: Some(*loc),
+            module: Some(id),
+        });
+
+        let res = self.execute_stmts(&stmts);
+
+        self.env = saved_env;
+        self.locals = saved_locals;
+        self.current_module = saved_module;
+        self.importing.pop();
+
+        match res {
+            Ok(()) => {
+                self.backtrace.pop();
+                Ok(Value::Module(id))
+            }
+            Err(Unwind::Throw(err)) => {
+                // let a later import try again
+                self.module_ids.remove(&key);
+                Err(err)
+            }
+            Err(unwind) => panic!(
+                "Internal interpreter error: {:?} at the top level of a module.",
+                unwind
+            ),
+        }
+    }
+
+    /// Scans, parses and resolves a module, rendering any errors as diagnostics.
+    fn load_module(
+        input: &input::Input,
+        dialect: Option<&dialect::Dialect>,
+    ) -> Result<(Vec<expr::Stmt>, resolver::Locals), String> {
+        let render = |kind: messages::Message, (message, span): (String, Option<diagnostics::Span>)| {
+            // on a line of its own, below the import error
+            format!(
+                "\n{}",
+                diagnostics::render(input, 1, &kind.to_string(), &message, span).trim_end()
+            )
+        };
+
+        let extra_keywords = dialect::extra_keywords(dialect);
+        let tokens = scanner::scan_tokens_with(input.content.clone(), extra_keywords).map_err(|err| {
+            render(
+                messages::Message::ScannerErrorLabel,
+                diagnostics::scanner_error(&err),
+            )
+        })?;
+        let stmts = parser::parse(tokens).map_err(|errs| {
+            errs.iter()
+                .map(|err| {
+                    render(
+                        messages::Message::SyntaxErrorLabel,
+                        diagnostics::parser_error(err),
+                    )
+                })
+                .collect::<String>()
+        })?;
+        let locals = resolver::resolve(&stmts).map_err(|err| {
+            render(
+                messages::Message::ResolveErrorLabel,
+                diagnostics::resolver_error(&err),
+            )
+        })?;
+
+        Ok((stmts, locals))
+    }
+
+    fn interpret_expr(&mut self, expr: &expr::Expr) -> Result<Value, RuntimeError> {
         if self.interrupted.load(Ordering::Acquire) {
-            return Ok(Value::Nil);
+            return Err(RuntimeError::Interrupted);
         }
 
         match expr {
-            expr::Expr::This(source_location) => match self.lookup(&Interpreter::this_symbol(
+            expr::Expr::This(source_location) => match self.lookup(&resolver::this_symbol(
                 source_location.line,
                 source_location.col,
             )) {
-                Ok(val) => Ok(val.clone()),
+                Ok(val) => Ok(val),
                 Err(err) => Err(err),
             },
             expr::Expr::Literal(lit) => Ok(Interpreter::interpret_literal(lit)),
             expr::Expr::Unary(op, e) => self.interpret_unary(*op, e),
             expr::Expr::Binary(lhs, op, rhs) => self.interpret_binary(lhs, *op, rhs),
             expr::Expr::Call(callee, loc, args) => self.call(callee, loc, args),
-            expr::Expr::Get(lhs, attr) => self.getattr(lhs, &attr.name),
+            expr::Expr::Get(lhs, attr) => self.getattr(lhs, attr),
             expr::Expr::Set(lhs, attr, rhs) => self.setattr(lhs, attr, rhs),
             expr::Expr::Grouping(e) => self.interpret_expr(e),
-            expr::Expr::Variable(sym) => match self.lookup(sym) {
-                Ok(val) => Ok(val.clone()),
-                Err(err) => Err(err),
-            },
+            expr::Expr::Variable(sym) => self.lookup(sym),
             expr::Expr::Assign(sym, val_expr) => {
                 let val = self.interpret_expr(val_expr)?;
 
-                self.env.assign(sym.clone(), &val)?;
+                self.assign(sym, &val)?;
 
                 Ok(val)
             }
@@ -873,40 +1804,300 @@ impl Interpreter {
                         Some(superclass_id) => {
                             let superclass = self.get_lox_class(*superclass_id);
                             if let Some((func_name, method_id)) = superclass.find_method(&sym.name, self) {
-                                let method = self.get_lox_function(method_id);
+                                let this_val = self.lookup(&resolver::this_symbol(
+                                    source_location.line,
+                                    source_location.col,
+                                ))?;
                                 Ok(Value::LoxFunction(
                                     func_name,
-                                    method.id,
-                                    func.this_binding.clone()))
+                                    method_id,
+                                    Some(Box::new(this_val))))
                             }
                             else {
-                                Err(format!("no superclass has method {} at line={}, col={}",
-                                            sym.name, source_location.line, source_location.col))
+                                Err(RuntimeError::NoSuperMethod {
+                                    name: sym.name.clone(),
+                                    loc: *source_location,
+                                })
                             }
                         }
                         _ => {
-                            Err(format!("Super expression not enclosed in a method definition at line={}, col={}.",
-                                        source_location.line, source_location.col))
+                            Err(RuntimeError::SuperOutsideMethod {
+                                loc: *source_location,
+                            })
                         }
                     }
                 }
-                None => Err(format!(
-                    "super expression not enclosed in a function at line={}, col={}.",
-                    source_location.line, source_location.col
-                )),
+                None => Err(RuntimeError::SuperOutsideMethod {
+                    loc: *source_location,
+                }),
             },
+            expr::Expr::Lambda(lambda_decl) => {
+                let lambda_id = self.alloc_id();
+                let lambda_name = self.alloc_lambda_name(&lambda_decl.source_location);
+                // like the closure, `सुपर` comes from the function the lambda is written in
+                let superclass = self
+                    .enclosing_function
+                    .and_then(|func_id| self.get_lox_function(func_id).superclass);
+
+                let lox_function = LoxFunction {
+                    id: lambda_id,
+                    name: lambda_name.clone(),
+                    parameters: lambda_decl.params.clone(),
+                    body: lambda_decl.body.clone(),
+                    closure: self.env.clone(),
+                    this_binding: None,
+                    superclass,
+                    is_initializer: false,
+                    locals: self.locals.clone(),
+                    module: self.current_module,
+                };
+
+                self.lox_functions.insert(lambda_id, lox_function);
+
+                Ok(Value::LoxFunction(lambda_name, lambda_id, None))
+            }
+            expr::Expr::List(elements) => {
+                let maybe_elts: Result<Vec<_>, _> = elements
+                    .iter()
+                    .map(|elt| self.interpret_expr(elt))
+                    .collect();
+                let elts = maybe_elts?;
+                Ok(self.create_list(elts))
+            }
+            expr::Expr::Interpolation(parts) => {
+                let mut res = String::new();
+                for part in parts {
+                    match self.interpret_expr(part)? {
+                        Value::String(s) => res.push_str(&s),
+                        val => res.push_str(&self.format_val(&val)),
+                    }
+                }
+                Ok(Value::String(res))
+            }
+            expr::Expr::Dict {
+                entries,
+                source_location,
+            } => {
+                let mut dict = Dict::default();
+                for (key_expr, val_expr) in entries {
+                    let key = self.interpret_expr(key_expr)?;
+                    let key = Interpreter::dict_key(&key, source_location)?;
+                    let val = self.interpret_expr(val_expr)?;
+                    dict.insert(key, val);
+                }
+                Ok(self.create_dict(dict))
+            }
+            expr::Expr::Subscript {
+                value,
+                slice,
+                source_location,
+            } => {
+                let value = self.interpret_expr(value)?;
+                let slice = self.interpret_expr(slice)?;
+                self.get_item(value, &slice, source_location)
+            }
+            expr::Expr::SetItem {
+                lhs,
+                slice,
+                rhs,
+                source_location,
+            } => {
+                let lhs = self.interpret_expr(lhs)?;
+                let slice = self.interpret_expr(slice)?;
+                let rhs = self.interpret_expr(rhs)?;
+                self.set_item(lhs, &slice, rhs, source_location)
+            }
+            expr::Expr::CompoundAssign { target, op, value } => {
+                self.compound_assign(target, *op, value)
+            }
+            expr::Expr::Increment { target, op } => {
+                let (old, _) = self.update(target, |interpreter, current| {
+                    interpreter.binary_op(current, *op, &Value::Number(1.0))
+                })?;
+                Ok(old)
+            }
+        }
+    }
+
+    fn get_item(
+        &self,
+        value: Value,
+        slice: &Value,
+        source_location: &expr::SourceLocation,
+    ) -> Result<Value, RuntimeError> {
+        match value {
+            Value::List(list_id) => {
+                let elts = self.get_list_elts(list_id);
+                let idx = Interpreter::list_index(slice, elts.len(), source_location)?;
+                Ok(elts[idx].clone())
+            }
+            Value::Dict(dict_id) => {
+                let key = Interpreter::dict_key(slice, source_location)?;
+                match self.get_dict(dict_id).get(&key) {
+                    Some(val) => Ok(val.clone()),
+                    None => Err(RuntimeError::KeyNotFound {
+                        key: self.format_val(slice),
+                        loc: *source_location,
+                    }),
+                }
+            }
+            _ => Err(RuntimeError::NotSubscriptable {
+                ty: type_of(&value),
+                loc: *source_location,
+            }),
+        }
+    }
+
+    fn set_item(
+        &mut self,
+        lhs: Value,
+        slice: &Value,
+        rhs: Value,
+        source_location: &expr::SourceLocation,
+    ) -> Result<Value, RuntimeError> {
+        match lhs {
+            Value::List(list_id) => {
+                let len = self.get_list_elts(list_id).len();
+                let idx = Interpreter::list_index(slice, len, source_location)?;
+                match self.lists.get_mut(&list_id) {
+                    Some(elts) => {
+                        elts[idx] = rhs.clone();
+                        Ok(rhs)
+                    }
+                    None => panic!(
+                        "Internal interpreter error! Couldn't find list with id {}.",
+                        list_id
+                    ),
+                }
+            }
+            Value::Dict(dict_id) => {
+                let key = Interpreter::dict_key(slice, source_location)?;
+                match self.dicts.get_mut(&dict_id) {
+                    Some(dict) => {
+                        dict.insert(key, rhs.clone());
+                        Ok(rhs)
+                    }
+                    None => panic!(
+                        "Internal interpreter error! Couldn't find dict with id {}.",
+                        dict_id
+                    ),
+                }
+            }
+            _ => Err(RuntimeError::NotSubscriptable {
+                ty: type_of(&lhs),
+                loc: *source_location,
+            }),
+        }
+    }
+
+    /// `target op= value`, where the parts of `target` (the object of a field, the
+    /// list and index of a subscript) are evaluated only once.
+    fn compound_assign(
+        &mut self,
+        target: &expr::Expr,
+        op: expr::BinaryOp,
+        value: &expr::Expr,
+    ) -> Result<Value, RuntimeError> {
+        let (_, val) = self.update(target, |interpreter, current| {
+            let rhs = interpreter.interpret_expr(value)?;
+            interpreter.binary_op(current, op, &rhs)
+        })?;
+        Ok(val)
+    }
+
+    /// Stores `new_value` of the current value of `target`, evaluating the parts of
+    /// `target` only once. Returns the old and the new value.
+    fn update(
+        &mut self,
+        target: &expr::Expr,
+        new_value: impl FnOnce(&mut Interpreter, &Value) -> Result<Value, RuntimeError>,
+    ) -> Result<(Value, Value), RuntimeError> {
+        match target {
+            expr::Expr::Variable(sym) => {
+                let current = self.lookup(sym)?;
+                let val = new_value(self, &current)?;
+                self.assign(sym, &val)?;
+                Ok((current, val))
+            }
+            expr::Expr::Get(lhs, attr) => {
+                let lhs = self.interpret_expr(lhs)?;
+                let current = self.getattr_of(lhs.clone(), attr)?;
+                let val = new_value(self, &current)?;
+                let val = self.setattr_of(lhs, attr, val)?;
+                Ok((current, val))
+            }
+            expr::Expr::Subscript {
+                value: lhs,
+                slice,
+                source_location,
+            } => {
+                let lhs = self.interpret_expr(lhs)?;
+                let slice = self.interpret_expr(slice)?;
+                let current = self.get_item(lhs.clone(), &slice, source_location)?;
+                let val = new_value(self, &current)?;
+                let val = self.set_item(lhs, &slice, val, source_location)?;
+                Ok((current, val))
+            }
+            _ => panic!("Internal interpreter error! Invalid compound assignment target."),
+        }
+    }
+    fn list_index(
+        slice: &Value,
+        len: usize,
+        source_location: &expr::SourceLocation,
+    ) -> Result<usize, RuntimeError> {
+        match slice {
+            Value::Number(n) => {
+                if n.fract() != 0.0 {
+                    return Err(RuntimeError::IndexNotInteger {
+                        index: *n,
+                        loc: *source_location,
+                    });
+                }
+                if *n < 0.0 || *n >= len as f64 {
+                    return Err(RuntimeError::IndexOutOfRange {
+                        index: *n,
+                        len,
+                        loc: *source_location,
+                    });
+                }
+                Ok(*n as usize)
+            }
+            _ => Err(RuntimeError::IndexNotNumber {
+                ty: type_of(slice),
+                loc: *source_location,
+            }),
         }
     }
 
 
-    fn getattr(&mut self, lhs: &expr::Expr, attr: &str) -> Result<Value, String> {
+    fn getattr(&mut self, lhs: &expr::Expr, attr: &expr::Symbol) -> Result<Value, RuntimeError> {
         let val = self.interpret_expr(lhs)?;
+        self.getattr_of(val, attr)
+    }
+
+    fn getattr_of(&self, val: Value, attr: &expr::Symbol) -> Result<Value, RuntimeError> {
         match val {
             Value::LoxInstance(_, id) => self.get_lox_instance(id).getattr(attr, self),
-            _ => Err(format!(
-                "Only LoxInstance values have attributes. Found {:?}.",
-                type_of(&val)
-            )),
+            Value::Module(id) => match self.get_module(id).namespace.lookup(attr) {
+                LookupResult::Ok(val) => Ok(val),
+                LookupResult::UndefButDeclared(declared_at) => {
+                    Err(RuntimeError::UndefinedButDeclared {
+                        name: attr.name.clone(),
+                        loc: attr.location(),
+                        declared_at,
+                    })
+                }
+                LookupResult::UndefAndNotDeclared => Err(RuntimeError::NoSuchModuleMember {
+                    module: self.module_name(id),
+                    name: attr.name.clone(),
+                    loc: attr.location(),
+                }),
+            },
+            _ => Err(RuntimeError::NoAttributes {
+                ty: type_of(&val),
+                loc: attr.location(),
+            }),
         }
     }
 
@@ -915,9 +2106,18 @@ impl Interpreter {
         lhs_exp: &expr::Expr,
         attr: &expr::Symbol,
         rhs_exp: &expr::Expr,
-    ) -> Result<Value, String> {
+    ) -> Result<Value, RuntimeError> {
         let lhs = self.interpret_expr(lhs_exp)?;
         let rhs = self.interpret_expr(rhs_exp)?;
+        self.setattr_of(lhs, attr, rhs)
+    }
+
+    fn setattr_of(
+        &mut self,
+        lhs: Value,
+        attr: &expr::Symbol,
+        rhs: Value,
+    ) -> Result<Value, RuntimeError> {
         match lhs {
             Value::LoxInstance(_, id) => match self.lox_instances.get_mut(&id) {
                 Some(inst) => {
@@ -929,10 +2129,10 @@ impl Interpreter {
                     id
                 ),
             },
-            _ => Err(format!(
-                "Only LoxInstance values have attributes. Found {:?}.",
-                type_of(&lhs)
-            )),
+            _ => Err(RuntimeError::NoAttributes {
+                ty: type_of(&lhs),
+                loc: attr.location(),
+            }),
         }
     }
 
@@ -941,7 +2141,7 @@ impl Interpreter {
         callee_expr: &expr::Expr,
         loc: &expr::SourceLocation,
         arg_exprs: &[expr::Expr],
-    ) -> Result<Value, String> {
+    ) -> Result<Value, RuntimeError> {
         let callee = self.interpret_expr(callee_expr)?;
 
         match as_callable(self, &callee) {
@@ -954,25 +2154,27 @@ impl Interpreter {
                 match maybe_args {
                     Ok(args) => {
                         if args.len() != callable.arity(self).into() {
-                            Err(format!(
-                                "Invalid call at line={},col={}: callee has arity {}, but \
-                                         was called with {} arguments",
-                                loc.line,
-                                loc.col,
-                                callable.arity(self),
-                                args.len()
-                            ))
+                            Err(RuntimeError::ArityMismatch {
+                                arity: callable.arity(self),
+                                num_args: args.len(),
+                                loc: *loc,
+                            })
                         } else {
-                            callable.call(self, &args)
+                            self.call_site = Some(*loc);
+                            let res = callable.call(self, &args);
+                            if res.is_ok() {
+                                self.call_site = None;
+                            }
+                            res.map_err(|err| err.at_call_site(*loc))
                         }
                     }
                     Err(err) => Err(err),
                 }
             }
-            None => Err(format!(
-                "value {:?} is not callable at line={},col={}",
-                callee, loc.line, loc.col
-            )),
+            None => Err(RuntimeError::NotCallable {
+                ty: type_of(&callee),
+                loc: *loc,
+            }),
         }
     }
 
@@ -981,11 +2183,19 @@ impl Interpreter {
         lhs_expr: &expr::Expr,
         op: expr::BinaryOp,
         rhs_expr: &expr::Expr,
-    ) -> Result<Value, String> {
+    ) -> Result<Value, RuntimeError> {
         let lhs = self.interpret_expr(lhs_expr)?;
         let rhs = self.interpret_expr(rhs_expr)?;
+        self.binary_op(&lhs, op, &rhs)
+    }
 
-        match (&lhs, op.ty, &rhs) {
+    fn binary_op(
+        &mut self,
+        lhs: &Value,
+        op: expr::BinaryOp,
+        rhs: &Value,
+    ) -> Result<Value, RuntimeError> {
+        match (lhs, op.ty, rhs) {
             (Value::Number(n1), expr::BinaryOpTy::Less, Value::Number(n2)) => {
                 Ok(Value::Bool(n1 < n2))
             }
@@ -1011,12 +2221,29 @@ impl Interpreter {
                 if *n2 != 0.0 {
                     Ok(Value::Number(n1 / n2))
                 } else {
-                    Err(format!(
-                        "division by zero at line={},col={}",
-                        op.line, op.col
-                    ))
+                    Err(RuntimeError::DivisionByZero {
+                        loc: expr::SourceLocation {
+                            line: op.line,
+                            col: op.col,
+                        },
+                    })
                 }
             }
+            (Value::Number(n1), expr::BinaryOpTy::Percent, Value::Number(n2)) => {
+                if *n2 != 0.0 {
+                    Ok(Value::Number(n1 % n2))
+                } else {
+                    Err(RuntimeError::DivisionByZero {
+                        loc: expr::SourceLocation {
+                            line: op.line,
+                            col: op.col,
+                        },
+                    })
+                }
+            }
+            (Value::Number(n1), expr::BinaryOpTy::StarStar, Value::Number(n2)) => {
+                Ok(Value::Number(n1.powf(*n2)))
+            }
             (Value::String(s1), expr::BinaryOpTy::Plus, Value::String(s2)) => {
                 Ok(Value::String(format!("{}{}", s1, s2)))
             }
@@ -1028,17 +2255,18 @@ impl Interpreter {
                 Ok(self.create_list(res))
             }
             (_, expr::BinaryOpTy::EqualEqual, _) => {
-                Ok(Value::Bool(Interpreter::equals(&lhs, &rhs)))
+                Ok(Value::Bool(Interpreter::equals(lhs, rhs)))
             }
-            (_, expr::BinaryOpTy::NotEqual, _) => Ok(Value::Bool(!Interpreter::equals(&lhs, &rhs))),
-            _ => Err(format!(
-                "invalid operands in binary operator {:?} of type {:?} and {:?} at line={},col={}",
-                op.ty,
-                type_of(&lhs),
-                type_of(&rhs),
-                op.line,
-                op.col
-            )),
+            (_, expr::BinaryOpTy::NotEqual, _) => Ok(Value::Bool(!Interpreter::equals(lhs, rhs))),
+            _ => Err(RuntimeError::InvalidBinaryOperands {
+                op: op.ty,
+                lhs: type_of(lhs),
+                rhs: type_of(rhs),
+                loc: expr::SourceLocation {
+                    line: op.line,
+                    col: op.col,
+                },
+            }),
         }
     }
 
@@ -1052,44 +2280,20 @@ impl Interpreter {
         }
     }
 
-    fn interpret_unary(&mut self, op: expr::UnaryOp, expr: &expr::Expr) -> Result<Value, String> {
+    fn interpret_unary(&mut self, op: expr::UnaryOp, expr: &expr::Expr) -> Result<Value, RuntimeError> {
         let val = self.interpret_expr(expr)?;
 
         match (op.ty, &val) {
             (expr::UnaryOpTy::Minus, Value::Number(n)) => Ok(Value::Number(-n)),
             (expr::UnaryOpTy::Bang, _) => Ok(Value::Bool(!Interpreter::is_truthy(&val))),
-            (_, Value::String(_)) => Err(format!(
-                "invalid application of unary op {:?} to object of type String at line={},col={}",
-                op.ty, op.line, op.col
-            )),
-            (_, Value::NativeFunction(_)) => Err(format!(
-                "invalid application of unary op {:?} to object of type NativeFunction at line={},col={}",
-                op.ty, op.line, op.col
-            )),
-            (_, Value::LoxFunction(_, _, _)) => Err(format!(
-                "invalid application of unary op {:?} to object of type LoxFunction at line={},col={}",
-                op.ty, op.line, op.col
-            )),
-            (_, Value::LoxClass(_, _)) => Err(format!(
-                "invalid application of unary op {:?} to object of type LoxClass at line={},col={}",
-                op.ty, op.line, op.col
-            )),
-            (_, Value::LoxInstance(class_name, _)) => Err(format!(
-                "invalid application of unary op {:?} to object of type {:?} at line={},col={}",
-                class_name.name, op.ty, op.line, op.col
-            )),
-            (expr::UnaryOpTy::Minus, Value::Bool(_)) => Err(format!(
-                "invalid application of unary op {:?} to object of type Bool at line={},col={}",
-                op.ty, op.line, op.col
-            )),
-            (_, Value::Nil) => Err(format!(
-                "invalid application of unary op {:?} to nil at line={},col={}",
-                op.ty, op.line, op.col
-            )),
-            (_, Value::List(_)) => Err(format!(
-                "invalid application of unary op {:?} to list at line={},col={}",
-                op.ty, op.line, op.col
-            )),
+            (_, _) => Err(RuntimeError::InvalidUnaryOperand {
+                op: op.ty,
+                ty: type_of(&val),
+                loc: expr::SourceLocation {
+                    line: op.line,
+                    col: op.col,
+                },
+            }),
         }
     }
 
@@ -1111,7 +2315,7 @@ impl Interpreter {
         }
     }
 
-    fn format_val(&self, val: &Value) -> String {
+    pub fn format_val(&self, val: &Value) -> String {
         match val {
             Value::Number(n) => format!("{}", n),
             Value::String(s) => format!("'{}'", s),
@@ -1121,6 +2325,7 @@ impl Interpreter {
             Value::LoxFunction(sym, _, _) => format!("LoxFunction({})", sym.name),
             Value::LoxClass(sym, _) => format!("LoxClass({})", sym.name),
             Value::LoxInstance(sym, _) => format!("LoxInstance({})", sym.name),
+            Value::Module(id) => format!("Module({})", self.module_name(*id)),
             Value::List(list_id) => {
                 let mut res = String::new();
                 write!(&mut res, "[").unwrap();
@@ -1134,6 +2339,21 @@ impl Interpreter {
                 write!(&mut res, "]").unwrap();
                 res
             }
+            Value::Dict(dict_id) => {
+                let entries: Vec<_> = self
+                    .get_dict(*dict_id)
+                    .entries()
+                    .iter()
+                    .map(|(key, val)| {
+                        format!(
+                            "{}: {}",
+                            self.format_val(&key.to_value()),
+                            self.format_val(val)
+                        )
+                    })
+                    .collect();
+                format!("{{{}}}", entries.join(", "))
+            }
         }
     }
 }
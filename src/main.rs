@@ -1,17 +1,35 @@
 extern crate clap;
 
-use clap::{App, Arg};
+use clap::{App, Arg, SubCommand};
 
 use std::fs;
+use std::path::Path;
 
+mod dialect;
+mod diagnostics;
+mod docs;
 mod expr;
+mod format;
 mod input;
+mod messages;
 mod parser;
+mod repl;
+mod resolver;
 mod scanner;
 mod interpreter;
+mod transliterate;
 
 const FILENAME: &str = "file";
 const INPUT: &str = "c";
+const LANG: &str = "lang";
+const TRANSLITERATE: &str = "transliterate";
+const TO: &str = "to";
+const IDENTIFIERS: &str = "identifiers";
+const DIGITS: &str = "digits";
+const DIALECT: &str = "dialect";
+const FMT: &str = "fmt";
+const CHECK: &str = "check";
+const DOC: &str = "doc";
 
 fn get_input(matches: &clap::ArgMatches<'_>) -> Option<input::Input> {
     if let Some(literal_input) = matches.value_of(INPUT) {
@@ -26,31 +44,286 @@ fn get_input(matches: &clap::ArgMatches<'_>) -> Option<input::Input> {
             Ok(input) => {
                 return Some(input::Input {
                     source: input::Source::File(input_file.to_string()),
-                    content: nepfy(input),
+                    content: input,
                 });
             }
             Err(err) => {
-                panic!("Error reading {}: {}", input_file, err);
+                eprintln!("Error reading {}: {}", input_file, err);
+                std::process::exit(-1);
             }
         }
     }
 
     None
 }
-fn nepfy(data: String) -> String {
-    data.chars().collect::<Vec<char>>().iter().map(|f| {
-        let nepali_numbers = ['०','१','२','३','४','५','६','७','८','९'];
-        if let Some(pos) = nepali_numbers.iter().position(|d|  d == f ) {
-            return char::from_digit(pos as u32,10).unwrap();
-        }
-        *f
-    }).collect::<String>()
+
+fn report(input: &input::Input, kind: &str, (message, span): (String, Option<diagnostics::Span>)) {
+    eprint!("{}", diagnostics::render(input, 1, kind, &message, span));
 }
 
-fn main() {
+/// The dialect `input` is written in: the one its pragma names, or else `cli_dialect`.
+fn source_dialect(
+    input: &input::Input,
+    cli_dialect: Option<&dialect::Dialect>,
+) -> Result<Option<dialect::Dialect>, ()> {
+    let dir = match &input.source {
+        input::Source::File(path) => Path::new(path).parent(),
+        _ => None,
+    };
+    dialect::for_source(&input.content, dir.unwrap_or_else(|| Path::new("")), cli_dialect).map_err(|err| {
+        let pragma_line = input.content.lines().next().unwrap_or("");
+        let span = diagnostics::Span {
+            line: 1,
+            col: 0,
+            len: pragma_line.chars().count(),
+        };
+        report(input, &messages::Message::DialectErrorLabel.to_string(), (err.to_string(), Some(span)));
+    })
+}
+
+fn run(input: &input::Input, cli_dialect: Option<&dialect::Dialect>) -> i32 {
+    let dialect = match source_dialect(input, cli_dialect) {
+        Ok(dialect) => dialect,
+        Err(()) => return -1,
+    };
+
+    let tokens = match scanner::scan_tokens_with(input.content.clone(), dialect::extra_keywords(dialect.as_ref())) {
+        Ok(tokens) => tokens,
+        Err(err) => {
+            report(input, &messages::Message::ScannerErrorLabel.to_string(), diagnostics::scanner_error(&err));
+            return -1;
+        }
+    };
+
+    let stmts = match parser::parse(tokens) {
+        Ok(stmts) => stmts,
+        Err(errs) => {
+            for err in errs.iter() {
+                report(input, &messages::Message::SyntaxErrorLabel.to_string(), diagnostics::parser_error(err));
+            }
+            return -1;
+        }
+    };
+
+    let locals = match resolver::resolve(&stmts) {
+        Ok(locals) => locals,
+        Err(err) => {
+            report(input, &messages::Message::ResolveErrorLabel.to_string(), diagnostics::resolver_error(&err));
+            return -1;
+        }
+    };
+
+    let mut interpreter: interpreter::Interpreter = Default::default();
+    interpreter.resolve(locals);
+    interpreter.dialect = cli_dialect.cloned();
+    if let input::Source::File(path) = &input.source {
+        interpreter.script_path = Some(path.into());
+    }
+
+    match interpreter.interpret(&stmts) {
+        Ok(_) => 0,
+        Err(err) => {
+            report(
+                interpreter.error_input().unwrap_or(input),
+                &messages::Message::RuntimeErrorLabel.to_string(),
+                diagnostics::runtime_error(&err),
+            );
+            eprintln!("\n{}", interpreter.format_backtrace(&err));
+            -1
+        }
+    }
+}
+
+fn transliterate(matches: &clap::ArgMatches<'_>, cli_dialect: Option<&dialect::Dialect>) -> i32 {
+    let input = match get_input(matches) {
+        Some(input) => input,
+        None => {
+            eprintln!("{}", messages::Message::MissingInput(TRANSLITERATE));
+            return -1;
+        }
+    };
+    let to = match matches.value_of(TO).map(str::parse).transpose() {
+        Ok(to) => to,
+        Err(err) => {
+            eprintln!("{}", err);
+            return -1;
+        }
+    };
+    let dialect = match source_dialect(&input, cli_dialect) {
+        Ok(dialect) => dialect,
+        Err(()) => return -1,
+    };
+    let options = transliterate::Options {
+        to,
+        dialect,
+        identifiers: matches.is_present(IDENTIFIERS),
+        digits: matches.is_present(DIGITS),
+    };
+
+    match transliterate::transliterate(&input.content, &options) {
+        Ok(output) => {
+            print!("{}", output);
+            0
+        }
+        Err(transliterate::Error::Scan(err)) => {
+            report(&input, &messages::Message::ScannerErrorLabel.to_string(), diagnostics::scanner_error(&err));
+            -1
+        }
+        Err(transliterate::Error::KeywordClash { name, keyword, line, col }) => {
+            let message = messages::Message::TransliterationKeywordClash {
+                name: &name,
+                keyword: &keyword,
+            };
+            let span = diagnostics::Span {
+                line,
+                col,
+                len: name.chars().count(),
+            };
+            report(
+                &input,
+                &messages::Message::TransliterationErrorLabel.to_string(),
+                (message.to_string(), Some(span)),
+            );
+            -1
+        }
+        Err(transliterate::Error::NameClash { name, other, new_name, line, col }) => {
+            let message = messages::Message::TransliterationNameClash {
+                name: &name,
+                other: &other,
+                new_name: &new_name,
+            };
+            let span = diagnostics::Span {
+                line,
+                col,
+                len: name.chars().count(),
+            };
+            report(
+                &input,
+                &messages::Message::TransliterationErrorLabel.to_string(),
+                (message.to_string(), Some(span)),
+            );
+            -1
+        }
+    }
+}
+
+fn fmt(matches: &clap::ArgMatches<'_>, cli_dialect: Option<&dialect::Dialect>) -> i32 {
+    let input = match get_input(matches) {
+        Some(input) => input,
+        None => {
+            eprintln!("{}", messages::Message::MissingInput(FMT));
+            return -1;
+        }
+    };
+    let dialect = match source_dialect(&input, cli_dialect) {
+        Ok(dialect) => dialect,
+        Err(()) => return -1,
+    };
+
+    let formatted = match format::format(&input.content, dialect::extra_keywords(dialect.as_ref())) {
+        Ok(formatted) => formatted,
+        Err(format::Error::Scan(err)) => {
+            report(&input, &messages::Message::ScannerErrorLabel.to_string(), diagnostics::scanner_error(&err));
+            return -1;
+        }
+        Err(format::Error::Parse(errs)) => {
+            for err in errs.iter() {
+                report(&input, &messages::Message::SyntaxErrorLabel.to_string(), diagnostics::parser_error(err));
+            }
+            return -1;
+        }
+    };
+
+    if matches.is_present(CHECK) {
+        // --c input needs no final newline of its own
+        let unchanged = formatted == input.content
+            || (matches!(input.source, input::Source::Literal)
+                && formatted.strip_suffix('\n') == Some(input.content.as_str()));
+        if unchanged {
+            return 0;
+        }
+        let name = diagnostics::source_name(&input.source);
+        eprintln!("{}", messages::Message::Unformatted(&name));
+        return 1;
+    }
+
+    match &input.source {
+        input::Source::File(path) => {
+            if formatted != input.content {
+                if let Err(err) = fs::write(path, formatted) {
+                    let reason = err.to_string();
+                    eprintln!("{}", messages::Message::WriteFailed { path, reason: &reason });
+                    return -1;
+                }
+            }
+        }
+        _ => print!("{}", formatted),
+    }
+    0
+}
+
+fn doc(matches: &clap::ArgMatches<'_>, cli_dialect: Option<&dialect::Dialect>) -> i32 {
+    let input = match get_input(matches) {
+        Some(input) => input,
+        None => {
+            eprintln!("{}", messages::Message::MissingInput(DOC));
+            return -1;
+        }
+    };
+    let dialect = match source_dialect(&input, cli_dialect) {
+        Ok(dialect) => dialect,
+        Err(()) => return -1,
+    };
+
+    let tokens = match scanner::scan_tokens_with(input.content.clone(), dialect::extra_keywords(dialect.as_ref())) {
+        Ok(tokens) => tokens,
+        Err(err) => {
+            report(&input, &messages::Message::ScannerErrorLabel.to_string(), diagnostics::scanner_error(&err));
+            return -1;
+        }
+    };
+
+    match parser::parse(tokens) {
+        Ok(stmts) => {
+            print!("{}", docs::render(&stmts));
+            0
+        }
+        Err(errs) => {
+            for err in errs.iter() {
+                report(&input, &messages::Message::SyntaxErrorLabel.to_string(), diagnostics::parser_error(err));
+            }
+            -1
+        }
+    }
+}
+
+/// Names that select a subcommand when given as the first positional argument.
+const SUBCOMMANDS: [&str; 4] = [TRANSLITERATE, FMT, DOC, "help"];
+
+/// Options that take their value from the next argument.
+const VALUE_OPTIONS: [&str; 4] = ["--c", "--lang", "--dialect", "--to"];
 
+/// Whether the first positional argument in `args` names a subcommand exactly. Anything else
+/// is a file to run, even if it looks like a subcommand name (`fmt.lox`, `docs`).
+fn subcommand_requested(args: &[String]) -> bool {
+    let mut args = args.iter().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            return false;
+        }
+        if !arg.starts_with('-') {
+            return SUBCOMMANDS.contains(&arg.as_str());
+        }
+        if VALUE_OPTIONS.contains(&arg.as_str()) {
+            args.next();
+        }
+    }
+    // without a positional argument there is nothing for clap to mistake for a subcommand
+    true
+}
 
-    let matches = App::new("nep preter")
+fn parse_args(args: Vec<String>) -> clap::Result<clap::ArgMatches<'static>> {
+    let app = App::new("nep preter")
         .version("1.0")
         .about("नेप-Preter")
         .author("Ashish Thapa").arg(
@@ -65,48 +338,168 @@ fn main() {
                 .takes_value(true)
                 .help("Parse String here"),
         )
-        .get_matches();
+        .arg(
+            Arg::with_name(LANG)
+                .long("lang")
+                .takes_value(true)
+                .help("Language of error messages: ne (default) or en"),
+        )
+        .arg(
+            Arg::with_name(DIALECT)
+                .long("dialect")
+                .takes_value(true)
+                .global(true)
+                .help("TOML file with extra keyword spellings"),
+        );
 
+    if !subcommand_requested(&args) {
+        return app.get_matches_from_safe(args);
+    }
+    app
+        .subcommand(
+            SubCommand::with_name(TRANSLITERATE)
+                .about("Rewrites a program's keywords between Devanagari and roman letters")
+                .arg(
+                    Arg::with_name(FILENAME)
+                        .help("Program to transliterate")
+                        .required(false)
+                        .index(1),
+                )
+                .arg(
+                    Arg::with_name(INPUT)
+                        .long("-c")
+                        .takes_value(true)
+                        .help("Transliterate String here"),
+                )
+                .arg(
+                    Arg::with_name(TO)
+                        .long("to")
+                        .takes_value(true)
+                        .help("devanagari or roman (default: the other script than the first keyword's)"),
+                )
+                .arg(
+                    Arg::with_name(IDENTIFIERS)
+                        .long("identifiers")
+                        .help("Also transliterate identifiers (except built-in names)"),
+                )
+                .arg(
+                    Arg::with_name(DIGITS)
+                        .long("digits")
+                        .help("Also convert digits in numbers"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name(FMT)
+                .about("Rewrites a program in the standard layout")
+                .arg(
+                    Arg::with_name(FILENAME)
+                        .help("Program to format in place")
+                        .required(false)
+                        .index(1),
+                )
+                .arg(
+                    Arg::with_name(INPUT)
+                        .long("-c")
+                        .takes_value(true)
+                        .help("Format String here and print the result"),
+                )
+                .arg(
+                    Arg::with_name(CHECK)
+                        .long("check")
+                        .help("Only check the layout; exit with 1 if the program is not formatted"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name(DOC)
+                .about("Lists a program's functions and classes with their /// docs")
+                .arg(
+                    Arg::with_name(FILENAME)
+                        .help("Program to document")
+                        .required(false)
+                        .index(1),
+                )
+                .arg(
+                    Arg::with_name(INPUT)
+                        .long("-c")
+                        .takes_value(true)
+                        .help("Document String here"),
+                ),
+        )
+        .get_matches_from_safe(args)
+}
 
-    if let Some(input) = get_input(&matches) {
-            match scanner::scan_tokens(input.content.clone()) {
-                Ok(tokens) => {
-                    // println!("{:#?}", tokens);
-
-                    let stmts_maybe = parser::parse(tokens);
-
-                    match stmts_maybe {
-                        Ok(stmts) => {
-                            // println!("{:#?}", stmts);
-                            let mut interpreter: interpreter::Interpreter =
-                                Default::default();
-                            let interpret_result = interpreter.interpret(&stmts);
-
-                            match interpret_result {
-                                Ok(_) => {
-                                    std::process::exit(0);
-                                }
-                                Err(err) => {
-                                    println!(
-                                        "Runtime Error: {}\n\n{}",
-                                        err,
-                                        interpreter.format_backtrace()
-                                    );
-                                    std::process::exit(-1);
-                                }
-                            }
-                        }
-                        Err(err) => {
-                            panic!("{:?} - {:?}",err,&input);
-                        }
-                    }
-                }
-                Err(err) => {
-                    panic!("{:?} - {:?}",err,&input);
-                }
+fn main() {
+    let matches = parse_args(std::env::args().collect()).unwrap_or_else(|err| err.exit());
+
+    let language = matches
+        .value_of(LANG)
+        .map(String::from)
+        .or_else(|| std::env::var(messages::LANGUAGE_ENV_VAR).ok());
+    if let Some(language) = language {
+        match language.parse() {
+            Ok(language) => messages::set_language(language),
+            Err(err) => {
+                eprintln!("{}", err);
+                std::process::exit(-1);
+            }
+        }
+    }
+
+    let cli_dialect = match matches.value_of(DIALECT) {
+        Some(path) => match dialect::load(Path::new(path)) {
+            Ok(dialect) => Some(dialect),
+            Err(err) => {
+                eprintln!("{}: {}", messages::Message::DialectErrorLabel, err);
+                std::process::exit(-1);
             }
-        
+        },
+        None => None,
+    };
+
+    if let Some(matches) = matches.subcommand_matches(TRANSLITERATE) {
+        std::process::exit(transliterate(matches, cli_dialect.as_ref()));
+    }
+
+    if let Some(matches) = matches.subcommand_matches(FMT) {
+        std::process::exit(fmt(matches, cli_dialect.as_ref()));
+    }
 
+    if let Some(matches) = matches.subcommand_matches(DOC) {
+        std::process::exit(doc(matches, cli_dialect.as_ref()));
     }
 
+    if let Some(input) = get_input(&matches) {
+        std::process::exit(run(&input, cli_dialect.as_ref()));
+    } else {
+        repl::run(cli_dialect);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn files_named_like_subcommands_are_run() {
+        for file in ["fmt.lox", "trans.lox", "doc.lox", "docs", "transliterate.lox"] {
+            let matches = parse_args(args(&["nep", file])).unwrap();
+            assert_eq!(matches.subcommand_name(), None);
+            assert_eq!(matches.value_of(FILENAME), Some(file));
+        }
+
+        let matches = parse_args(args(&["nep", "--lang", "en", "fmt.lox"])).unwrap();
+        assert_eq!(matches.value_of(FILENAME), Some("fmt.lox"));
+    }
+
+    #[test]
+    fn subcommands_are_recognized() {
+        let matches = parse_args(args(&["nep", "--lang", "en", "fmt", "--check", "fmt.lox"])).unwrap();
+        let fmt_matches = matches.subcommand_matches(FMT).unwrap();
+        assert!(fmt_matches.is_present(CHECK));
+        assert_eq!(fmt_matches.value_of(FILENAME), Some("fmt.lox"));
+    }
 }
@@ -0,0 +1,904 @@
+//! Every user-facing error message, in Nepali and in English.
+//!
+//! Errors are described by a [`Message`] keyed by the kind of error; its `Display` impl
+//! picks the wording for the language selected with [`set_language`] (from the `--lang`
+//! flag or the `NEP_LANG` environment variable).
+
+use crate::expr;
+use crate::interpreter;
+use crate::parser;
+use crate::scanner;
+
+use std::fmt;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+pub const LANGUAGE_ENV_VAR: &str = "NEP_LANG";
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Language {
+    Nepali,
+    English,
+}
+
+impl FromStr for Language {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Language, String> {
+        match s.to_lowercase().as_str() {
+            "ne" | "np" | "nepali" | "नेपाली" => Ok(Language::Nepali),
+            "en" | "english" | "अङ्ग्रेजी" => Ok(Language::English),
+            _ => Err(format!(
+                "unknown language '{}' (expected 'ne' or 'en') / अज्ञात भाषा '{}' ('ne' वा 'en' हुनुपर्छ)",
+                s, s
+            )),
+        }
+    }
+}
+
+static LANGUAGE: AtomicU8 = AtomicU8::new(0);
+
+pub fn set_language(language: Language) {
+    let val = match language {
+        Language::Nepali => 0,
+        Language::English => 1,
+    };
+    LANGUAGE.store(val, Ordering::Relaxed);
+}
+
+pub fn language() -> Language {
+    match LANGUAGE.load(Ordering::Relaxed) {
+        0 => Language::Nepali,
+        _ => Language::English,
+    }
+}
+
+macro_rules! tr {
+    ($f:expr, $ne:literal, $en:literal $(, $arg:expr)* $(,)?) => {
+        match language() {
+            Language::Nepali => write!($f, $ne $(, $arg)*),
+            Language::English => write!($f, $en $(, $arg)*),
+        }
+    };
+}
+
+/// What the parser was looking for when it found an unexpected token.
+#[derive(Debug, Copy, Clone)]
+pub enum Expected {
+    ClassName,
+    SuperclassName,
+    LeftBraceAfterClassName,
+    RightBraceAfterClassBody,
+    FunctionName(parser::FunctionKind),
+    LeftParenAfterFunctionName(parser::FunctionKind),
+    ParameterName,
+    RightParenAfterParameters,
+    LeftBraceBeforeFunctionBody,
+    VariableName,
+    SemicolonAfterVarDecl,
+    SemicolonAfterReturnValue,
+    SemicolonAfterLoopControl(scanner::TokenType),
+    SemicolonAfterThrowValue,
+    ModuleName,
+    EqualAfterModuleName,
+    ModulePath,
+    SemicolonAfterImport,
+    LeftBraceAfterTry,
+    CatchOrFinallyAfterTry,
+    LeftParenAfterCatch,
+    CatchVariableName,
+    RightParenAfterCatchVariable,
+    LeftBraceAfterCatch,
+    LeftBraceAfterFinally,
+    LeftParenAfterFor,
+    SemicolonAfterLoopCondition,
+    RightParenAfterForClauses,
+    LeftParenAfterWhile,
+    RightParenAfterWhileCondition,
+    LeftParenAfterIf,
+    RightParenAfterIfCondition,
+    RightBraceAfterBlock,
+    SemicolonAfterValue,
+    SemicolonAfterExpression,
+    PropertyNameAfterDot,
+    RightBracketAfterSubscript,
+    RightParenAfterArguments,
+    DotAfterSuper,
+    SuperclassMethodName,
+    RightParenAfterExpression,
+    RightBracketAfterListElements,
+    ColonAfterDictKey,
+    RightBraceAfterDictEntries,
+    RightBraceAfterInterpolation,
+}
+
+impl fmt::Display for Expected {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Expected::ClassName => tr!(f, "वर्ग (class) को नाम", "a class name"),
+            Expected::SuperclassName => tr!(f, "'<' पछि अभिभावक वर्गको नाम", "a superclass name after '<'"),
+            Expected::LeftBraceAfterClassName => tr!(f, "वर्गको नाम पछि '{{'", "'{{' after the class name"),
+            Expected::RightBraceAfterClassBody => tr!(f, "वर्गको भाग पछि '}}'", "'}}' after the class body"),
+            Expected::FunctionName(kind) => tr!(f, "{} को नाम", "a {} name", FunctionKindName(*kind)),
+            Expected::LeftParenAfterFunctionName(kind) => {
+                tr!(f, "{} को नाम पछि '('", "'(' after the {} name", FunctionKindName(*kind))
+            }
+            Expected::ParameterName => tr!(f, "प्यारामिटरको नाम", "a parameter name"),
+            Expected::RightParenAfterParameters => tr!(f, "प्यारामिटरहरू पछि ')'", "')' after the parameters"),
+            Expected::LeftBraceBeforeFunctionBody => {
+                tr!(f, "कार्यको भाग अघि '{{'", "'{{' before the function body")
+            }
+            Expected::VariableName => tr!(f, "भेरिएबलको नाम", "a variable name"),
+            Expected::SemicolonAfterVarDecl => {
+                tr!(f, "भेरिएबल घोषणा पछि ';'", "';' after the variable declaration")
+            }
+            Expected::SemicolonAfterReturnValue => tr!(f, "रिटन भ्यालु पछि ';'", "';' after the return value"),
+            Expected::SemicolonAfterLoopControl(ty) => {
+                tr!(f, "{} पछि ';'", "';' after {}", TokenName(*ty))
+            }
+            Expected::SemicolonAfterThrowValue => tr!(f, "फाल्ने भ्यालु पछि ';'", "';' after the thrown value"),
+            Expected::ModuleName => tr!(f, "'आयात' पछि मोड्युलको नाम", "a module name after 'आयात' (import)"),
+            Expected::EqualAfterModuleName => tr!(f, "मोड्युलको नाम पछि '='", "'=' after the module name"),
+            Expected::ModulePath => tr!(f, "मोड्युलको फाइल (स्ट्रिङ)", "the module's file path as a string"),
+            Expected::SemicolonAfterImport => tr!(f, "'आयात' पछि ';'", "';' after the import"),
+            Expected::LeftBraceAfterTry => tr!(f, "'प्रयास' पछि '{{'", "'{{' after 'प्रयास' (try)"),
+            Expected::CatchOrFinallyAfterTry => tr!(
+                f,
+                "'प्रयास' को ब्लक पछि 'समात' वा 'अन्त्यमा'",
+                "'समात' (catch) or 'अन्त्यमा' (finally) after the 'प्रयास' (try) block"
+            ),
+            Expected::LeftParenAfterCatch => tr!(f, "'समात' पछि '('", "'(' after 'समात' (catch)"),
+            Expected::CatchVariableName => tr!(f, "समातिएको त्रुटिको नाम", "a name for the caught error"),
+            Expected::RightParenAfterCatchVariable => {
+                tr!(f, "समातिएको त्रुटिको नाम पछि ')'", "')' after the caught error's name")
+            }
+            Expected::LeftBraceAfterCatch => tr!(f, "'समात' पछि '{{'", "'{{' after 'समात' (catch)"),
+            Expected::LeftBraceAfterFinally => tr!(f, "'अन्त्यमा' पछि '{{'", "'{{' after 'अन्त्यमा' (finally)"),
+            Expected::LeftParenAfterFor => tr!(f, "'भुमरी' पछि '('", "'(' after 'भुमरी' (for)"),
+            Expected::SemicolonAfterLoopCondition => tr!(f, "लुपको सर्त पछि ';'", "';' after the loop condition"),
+            Expected::RightParenAfterForClauses => {
+                tr!(f, "'भुमरी' का खण्डहरू पछि ')'", "')' after the 'भुमरी' (for) clauses")
+            }
+            Expected::LeftParenAfterWhile => tr!(f, "'जबसम्म' पछि '('", "'(' after 'जबसम्म' (while)"),
+            Expected::RightParenAfterWhileCondition => {
+                tr!(f, "'जबसम्म' को सर्त पछि ')'", "')' after the 'जबसम्म' (while) condition")
+            }
+            Expected::LeftParenAfterIf => tr!(f, "'यदि' पछि '('", "'(' after 'यदि' (if)"),
+            Expected::RightParenAfterIfCondition => {
+                tr!(f, "'यदि' को सर्त पछि ')'", "')' after the 'यदि' (if) condition")
+            }
+            Expected::RightBraceAfterBlock => tr!(f, "ब्लक पछि '}}'", "'}}' after the block"),
+            Expected::SemicolonAfterValue => tr!(f, "भ्यालु पछि ';'", "';' after the value"),
+            Expected::SemicolonAfterExpression => tr!(f, "अभिव्यक्ति पछि ';'", "';' after the expression"),
+            Expected::PropertyNameAfterDot => tr!(f, "'.' पछि गुणको नाम", "a property name after '.'"),
+            Expected::RightBracketAfterSubscript => tr!(f, "सूचकाङ्क पछि ']'", "']' after the subscript"),
+            Expected::RightParenAfterArguments => tr!(f, "आर्गुमेन्टहरू पछि ')'", "')' after the arguments"),
+            Expected::DotAfterSuper => tr!(f, "'सुपर' पछि '.'", "'.' after 'सुपर' (super)"),
+            Expected::SuperclassMethodName => {
+                tr!(f, "अभिभावक वर्गको मेथडको नाम", "a superclass method name")
+            }
+            Expected::RightParenAfterExpression => tr!(f, "अभिव्यक्ति पछि ')'", "')' after the expression"),
+            Expected::RightBracketAfterListElements => {
+                tr!(f, "सूचीका तत्वहरू पछि ']'", "']' after the list elements")
+            }
+            Expected::ColonAfterDictKey => tr!(f, "शब्दकोशको कुञ्जी पछि ':'", "':' after the dict key"),
+            Expected::RightBraceAfterDictEntries => {
+                tr!(f, "शब्दकोशका प्रविष्टिहरू पछि '}}'", "'}}' after the dict entries")
+            }
+            Expected::RightBraceAfterInterpolation => {
+                tr!(f, "'${{' भित्रको अभिव्यक्ति पछि '}}'", "'}}' after the expression in '${{'")
+            }
+        }
+    }
+}
+
+pub struct FunctionKindName(pub parser::FunctionKind);
+
+impl fmt::Display for FunctionKindName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            parser::FunctionKind::Function => tr!(f, "कार्य (function)", "function"),
+            parser::FunctionKind::Method => tr!(f, "मेथड (method)", "method"),
+            parser::FunctionKind::Lambda => tr!(f, "बेनामी कार्य (lambda)", "lambda"),
+        }
+    }
+}
+
+pub struct TokenName(pub scanner::TokenType);
+
+impl fmt::Display for TokenName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use scanner::TokenType;
+
+        let symbol = match self.0 {
+            TokenType::LeftParen => "'('",
+            TokenType::RightParen => "')'",
+            TokenType::LeftBrace => "'{'",
+            TokenType::RightBrace => "'}'",
+            TokenType::LeftBracket => "'['",
+            TokenType::RightBracket => "']'",
+            TokenType::Colon => "':'",
+            TokenType::Comma => "','",
+            TokenType::Dot => "'.'",
+            TokenType::Minus => "'-'",
+            TokenType::Percent => "'%'",
+            TokenType::Plus => "'+'",
+            TokenType::Semicolon => "';'",
+            TokenType::Slash => "'/'",
+            TokenType::Star => "'*'",
+            TokenType::StarStar => "'**'",
+            TokenType::StarEqual => "'*='",
+            TokenType::PlusEqual => "'+='",
+            TokenType::MinusEqual => "'-='",
+            TokenType::PlusPlus => "'++'",
+            TokenType::MinusMinus => "'--'",
+            TokenType::SlashEqual => "'/='",
+            TokenType::Bang => "'!'",
+            TokenType::BangEqual => "'!='",
+            TokenType::Equal => "'='",
+            TokenType::EqualEqual => "'=='",
+            TokenType::Greater => "'>'",
+            TokenType::GreaterEqual => "'>='",
+            TokenType::Less => "'<'",
+            TokenType::LessEqual => "'<='",
+            TokenType::And => "'अनि'",
+            TokenType::Break => "'टुट'",
+            TokenType::Catch => "'समात'",
+            TokenType::Class => "'वर्ग'",
+            TokenType::Continue => "'अर्को'",
+            TokenType::Else => "'अरु'",
+            TokenType::False => "'गलत'",
+            TokenType::Finally => "'अन्त्यमा'",
+            TokenType::Fun => "'काम'",
+            TokenType::For => "'भुमरी'",
+            TokenType::If => "'यदि'",
+            TokenType::Import => "'आयात'",
+            TokenType::Nil => "'आलु'",
+            TokenType::Or => "'वा'",
+            TokenType::Print => "'छाप'",
+            TokenType::Return => "'रिटन'",
+            TokenType::Super => "'सुपर'",
+            TokenType::This => "'यो'",
+            TokenType::Throw => "'फाल'",
+            TokenType::True => "'सहि'",
+            TokenType::Try => "'प्रयास'",
+            TokenType::Var => "'भार'",
+            TokenType::While => "'जबसम्म'",
+            TokenType::Identifier => return tr!(f, "नाम (identifier)", "identifier"),
+            TokenType::String => return tr!(f, "स्ट्रिङ", "string"),
+            TokenType::Interpolation => return tr!(f, "स्ट्रिङको '${{'", "'${{' in a string"),
+            TokenType::InterpolationMiddle | TokenType::InterpolationEnd => {
+                return tr!(f, "स्ट्रिङको '}}'", "'}}' in a string")
+            }
+            TokenType::Number => return tr!(f, "सङ्ख्या", "number"),
+            TokenType::Eof => return tr!(f, "फाइलको अन्त्य", "end of file"),
+        };
+        write!(f, "{}", symbol)
+    }
+}
+
+pub struct TypeName(pub interpreter::Type);
+
+impl fmt::Display for TypeName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use interpreter::Type;
+
+        match self.0 {
+            Type::Number => tr!(f, "सङ्ख्या (Number)", "Number"),
+            Type::String => tr!(f, "स्ट्रिङ (String)", "String"),
+            Type::Bool => tr!(f, "बुलियन (Bool)", "Bool"),
+            Type::Nil => tr!(f, "आलु (nil)", "nil"),
+            Type::NativeFunction => tr!(f, "नेटिभ कार्य (NativeFunction)", "NativeFunction"),
+            Type::LoxFunction => tr!(f, "कार्य (function)", "function"),
+            Type::LoxClass => tr!(f, "वर्ग (class)", "class"),
+            Type::LoxInstance => tr!(f, "वस्तु (instance)", "instance"),
+            Type::List => tr!(f, "सूची (list)", "list"),
+            Type::Module => tr!(f, "मोड्युल (module)", "module"),
+            Type::Dict => tr!(f, "शब्दकोश (dict)", "dict"),
+        }
+    }
+}
+
+pub struct UnaryOpName(pub expr::UnaryOpTy);
+
+impl fmt::Display for UnaryOpName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            expr::UnaryOpTy::Minus => write!(f, "'-'"),
+            expr::UnaryOpTy::Bang => write!(f, "'!'"),
+        }
+    }
+}
+
+pub struct BinaryOpName(pub expr::BinaryOpTy);
+
+impl fmt::Display for BinaryOpName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let symbol = match self.0 {
+            expr::BinaryOpTy::EqualEqual => "==",
+            expr::BinaryOpTy::NotEqual => "!=",
+            expr::BinaryOpTy::Less => "<",
+            expr::BinaryOpTy::LessEqual => "<=",
+            expr::BinaryOpTy::Greater => ">",
+            expr::BinaryOpTy::GreaterEqual => ">=",
+            expr::BinaryOpTy::Plus => "+",
+            expr::BinaryOpTy::Minus => "-",
+            expr::BinaryOpTy::Star => "*",
+            expr::BinaryOpTy::Slash => "/",
+            expr::BinaryOpTy::Percent => "%",
+            expr::BinaryOpTy::StarStar => "**",
+        };
+        write!(f, "'{}'", symbol)
+    }
+}
+
+pub enum Message<'a> {
+    // labels
+    ScannerErrorLabel,
+    SyntaxErrorLabel,
+    ResolveErrorLabel,
+    RuntimeErrorLabel,
+    TransliterationErrorLabel,
+    DialectErrorLabel,
+    BacktraceHeader,
+    ScriptFrame,
+    BacktraceFrame {
+        name: &'a str,
+        line: Option<usize>,
+        declared_at: Option<usize>,
+    },
+
+    // scanner
+    UnexpectedCharacter(char),
+    UnterminatedString,
+    UnterminatedComment,
+    UnknownEscape(char),
+    InvalidUnicodeEscape,
+
+    // dialects
+    DialectUnreadable {
+        path: &'a str,
+        reason: &'a str,
+    },
+    DialectInvalid {
+        path: &'a str,
+        reason: &'a str,
+    },
+    DialectNoKeywords(&'a str),
+    DialectNotAString {
+        path: &'a str,
+        keyword: &'a str,
+    },
+    DialectUnknownKeyword {
+        path: &'a str,
+        keyword: &'a str,
+    },
+    DialectInvalidSpelling {
+        path: &'a str,
+        spelling: &'a str,
+    },
+    DialectCollision {
+        path: &'a str,
+        spelling: &'a str,
+        first: scanner::TokenType,
+        second: scanner::TokenType,
+    },
+
+    // transliteration
+    TransliterationKeywordClash {
+        name: &'a str,
+        keyword: &'a str,
+    },
+    TransliterationNameClash {
+        name: &'a str,
+        other: &'a str,
+        new_name: &'a str,
+    },
+
+    // formatter
+    Unformatted(&'a str),
+
+    // command line
+    /// The subcommand that was given neither a file nor `--c`.
+    MissingInput(&'a str),
+    WriteFailed {
+        path: &'a str,
+        reason: &'a str,
+    },
+
+    // parser
+    UnexpectedToken(scanner::TokenType),
+    TokenMismatch {
+        expected: Expected,
+        found: scanner::TokenType,
+    },
+    MaxParamsExceeded(parser::FunctionKind),
+    InvalidAssignment,
+    TooManyArguments,
+    ExpectedExpression(scanner::TokenType),
+    InvalidTokenInUnaryOp(scanner::TokenType),
+    InvalidTokenInBinaryOp(scanner::TokenType),
+    LoopControlOutsideLoop(scanner::TokenType),
+
+    // resolver
+    ReadLocalInOwnInitializer(&'a str),
+    ReturnNotInFun,
+    ThisOutsideClass,
+
+    // runtime
+    Interrupted,
+    UncaughtThrow(&'a str),
+    ImportFailed {
+        path: &'a str,
+        reason: &'a str,
+    },
+    ImportCycle(&'a str),
+    NoSuchModuleMember {
+        module: &'a str,
+        name: &'a str,
+    },
+    UndefinedVariable {
+        name: &'a str,
+    },
+    UndefinedButDeclared {
+        name: &'a str,
+        decl_line: usize,
+        decl_col: i64,
+    },
+    AssignToUndeclared {
+        name: &'a str,
+    },
+    InitReturnsNonNil(interpreter::Type),
+    NoSuchAttribute {
+        class_name: &'a str,
+        attr: &'a str,
+    },
+    NoAttributes(interpreter::Type),
+    ClassInheritsFromItself {
+        name: &'a str,
+    },
+    SuperclassNotClass(interpreter::Type),
+    NoSuperMethod {
+        name: &'a str,
+    },
+    SuperOutsideMethod,
+    NotCallable {
+        ty: interpreter::Type,
+    },
+    ArityMismatch {
+        arity: u8,
+        num_args: usize,
+    },
+    DivisionByZero,
+    InvalidBinaryOperands {
+        op: expr::BinaryOpTy,
+        lhs: interpreter::Type,
+        rhs: interpreter::Type,
+    },
+    InvalidUnaryOperand {
+        op: expr::UnaryOpTy,
+        ty: interpreter::Type,
+    },
+    NotSubscriptable {
+        ty: interpreter::Type,
+    },
+    IndexNotNumber {
+        ty: interpreter::Type,
+    },
+    IndexNotInteger {
+        index: f64,
+    },
+    IndexOutOfRange {
+        index: f64,
+        len: usize,
+    },
+    InvalidKey {
+        ty: interpreter::Type,
+    },
+    KeyNotFound {
+        key: &'a str,
+    },
+    NoLen(interpreter::Type),
+    InvalidArgument {
+        function: &'a str,
+        position: usize,
+        ty: interpreter::Type,
+    },
+    ArgumentNotCallable {
+        function: &'a str,
+        position: usize,
+        ty: interpreter::Type,
+    },
+}
+
+impl fmt::Display for Message<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Message::ScannerErrorLabel => tr!(f, "स्क्यानर त्रुटि", "scanner error"),
+            Message::SyntaxErrorLabel => tr!(f, "वाक्य रचना त्रुटि", "syntax error"),
+            Message::ResolveErrorLabel => tr!(f, "त्रुटि", "error"),
+            Message::RuntimeErrorLabel => tr!(f, "रनटाइम त्रुटि", "runtime error"),
+            Message::TransliterationErrorLabel => tr!(f, "लिप्यन्तरण त्रुटि", "transliteration error"),
+            Message::DialectErrorLabel => tr!(f, "बोली त्रुटि", "dialect error"),
+            Message::BacktraceHeader => tr!(
+                f,
+                "ब्याकट्रेस (सबैभन्दा पछिल्लो कल अन्तिममा):",
+                "Backtrace (most recent call last):"
+            ),
+            Message::ScriptFrame => tr!(f, "स्क्रिप्ट", "script"),
+            Message::BacktraceFrame {
+                name,
+                line,
+                declared_at,
+            } => {
+                let line = match line {
+                    Some(line) => line.to_string(),
+                    None => String::from("??"),
+                };
+                tr!(f, "रेखा {} मा {}", "line {} in {}", line, name)?;
+                match declared_at {
+                    Some(decl_line) => tr!(
+                        f,
+                        " (रेखा {} मा घोषित)",
+                        " (declared at line {})",
+                        decl_line
+                    ),
+                    None => Ok(()),
+                }
+            }
+
+            Message::UnexpectedCharacter(c) => {
+                tr!(f, "स्क्यानरले '{}' बुझ्न सकेन", "scanner can't handle '{}'", c)
+            }
+            Message::UnterminatedString => tr!(f, "स्ट्रिङ बन्द गरिएको छैन", "unterminated string"),
+            Message::UnterminatedComment => tr!(f, "टिप्पणी बन्द गरिएको छैन", "unterminated comment"),
+            Message::UnknownEscape(c) => {
+                tr!(f, "अज्ञात एस्केप '\\{}'", "unknown escape sequence '\\{}'", c)
+            }
+            Message::DialectUnreadable { path, reason } => tr!(
+                f,
+                "बोली फाइल '{}' पढ्न सकिएन: {}",
+                "could not read dialect file '{}': {}",
+                path,
+                reason
+            ),
+            Message::DialectInvalid { path, reason } => tr!(
+                f,
+                "'{}' मान्य बोली फाइल होइन: {}",
+                "'{}' is not a valid dialect file: {}",
+                path,
+                reason
+            ),
+            Message::DialectNoKeywords(path) => tr!(
+                f,
+                "'{}' मा [keywords] तालिका छैन",
+                "'{}' has no [keywords] table",
+                path
+            ),
+            Message::DialectNotAString { path, keyword } => tr!(
+                f,
+                "'{}' मा '{}' को मान स्ट्रिङ वा स्ट्रिङहरूको सूची हुनुपर्छ",
+                "in '{}', '{}' must be a string or a list of strings",
+                path,
+                keyword
+            ),
+            Message::DialectUnknownKeyword { path, keyword } => tr!(
+                f,
+                "'{}' मा '{}' भन्ने कुञ्जी शब्द छैन",
+                "in '{}', '{}' is not a keyword",
+                path,
+                keyword
+            ),
+            Message::DialectInvalidSpelling { path, spelling } => tr!(
+                f,
+                "'{}' मा '{}' नाम (identifier) जस्तो छैन, त्यसैले कुञ्जी शब्द हुन सक्दैन",
+                "in '{}', '{}' is not spelled like an identifier, so it can't be a keyword",
+                path,
+                spelling
+            ),
+            Message::DialectCollision {
+                path,
+                spelling,
+                first,
+                second,
+            } => tr!(
+                f,
+                "'{}' मा '{}' {} र {} दुवैका लागि प्रयोग भएको छ",
+                "in '{}', '{}' is used for both {} and {}",
+                path,
+                spelling,
+                TokenName(*first),
+                TokenName(*second)
+            ),
+            Message::TransliterationKeywordClash { name, keyword } => tr!(
+                f,
+                "'{}' लाई लिप्यन्तरण गर्दा कुञ्जी शब्द '{}' बन्छ; यसलाई अर्को नाम दिनुहोस्",
+                "transliterating '{}' gives the keyword '{}'; rename it first",
+                name,
+                keyword
+            ),
+            Message::TransliterationNameClash { name, other, new_name } => tr!(
+                f,
+                "लिप्यन्तरण गर्दा '{}' र '{}' दुवैको नाम '{}' हुन्छ; एउटालाई अर्को नाम दिनुहोस्",
+                "'{}' and '{}' would both be named '{}' after transliteration; rename one first",
+                name,
+                other,
+                new_name
+            ),
+            Message::Unformatted(path) => tr!(
+                f,
+                "{} को ढाँचा मिलेको छैन",
+                "{} is not formatted",
+                path
+            ),
+            Message::MissingInput(subcommand) => tr!(
+                f,
+                "'{}' लाई फाइल वा --c दिनुहोस्",
+                "please provide a file or --c to '{}'",
+                subcommand
+            ),
+            Message::WriteFailed { path, reason } => tr!(
+                f,
+                "{} मा लेख्न सकिएन: {}",
+                "could not write {}: {}",
+                path,
+                reason
+            ),
+            Message::InvalidUnicodeEscape => tr!(
+                f,
+                "'\\u' पछि {{}} भित्र १ देखि ६ वटा हेक्स अङ्क भएको मान्य युनिकोड चाहिन्छ",
+                "'\\u' must be followed by a valid unicode value of 1 to 6 hex digits in {{}}"
+            ),
+
+            Message::UnexpectedToken(ty) => {
+                tr!(f, "अनपेक्षित टोकन {}", "unexpected token {}", TokenName(*ty))
+            }
+            Message::TokenMismatch { expected, found } => tr!(
+                f,
+                "{} चाहिएको थियो, तर {} भेटियो",
+                "expected {}, but found {}",
+                expected,
+                TokenName(*found)
+            ),
+            Message::MaxParamsExceeded(kind) => tr!(
+                f,
+                "{} मा २५५ भन्दा बढी प्यारामिटर हुन सक्दैनन्",
+                "a {} can't have more than 255 parameters",
+                FunctionKindName(*kind)
+            ),
+            Message::InvalidAssignment => tr!(f, "अवैध असाइनमेन्ट लक्ष्य", "invalid assignment target"),
+            Message::TooManyArguments => tr!(
+                f,
+                "२५५ भन्दा बढी आर्गुमेन्ट दिन मिल्दैन",
+                "can't have more than 255 arguments"
+            ),
+            Message::ExpectedExpression(ty) => tr!(
+                f,
+                "अभिव्यक्ति चाहिएको थियो, तर {} भेटियो",
+                "expected an expression, but found {}",
+                TokenName(*ty)
+            ),
+            Message::LoopControlOutsideLoop(ty) => tr!(
+                f,
+                "{} लुप भित्र मात्र प्रयोग गर्न मिल्छ",
+                "can't use {} outside of a loop",
+                TokenName(*ty)
+            ),
+            Message::InvalidTokenInUnaryOp(ty) => tr!(
+                f,
+                "एकल (unary) अपरेटरमा अवैध टोकन {}",
+                "invalid token {} in unary operator",
+                TokenName(*ty)
+            ),
+            Message::InvalidTokenInBinaryOp(ty) => tr!(
+                f,
+                "द्वि (binary) अपरेटरमा अवैध टोकन {}",
+                "invalid token {} in binary operator",
+                TokenName(*ty)
+            ),
+
+            Message::ReadLocalInOwnInitializer(name) => tr!(
+                f,
+                "भेरिएबल '{}' लाई आफ्नै initializer मा पढ्न मिल्दैन",
+                "can't read local variable '{}' in its own initializer",
+                name
+            ),
+            Message::ReturnNotInFun => tr!(
+                f,
+                "'रिटन' कार्य (function) भित्र मात्र प्रयोग गर्न मिल्छ",
+                "can't 'रिटन' (return) from top-level code"
+            ),
+            Message::ThisOutsideClass => tr!(
+                f,
+                "'यो' वर्ग (class) बाहिर प्रयोग गर्न मिल्दैन",
+                "can't use 'यो' (this) outside of a class"
+            ),
+
+            Message::Interrupted => tr!(f, "रोकियो।", "Interrupted."),
+            Message::UncaughtThrow(description) => {
+                tr!(f, "नसमातिएको अपवाद: {}", "uncaught exception: {}", description)
+            }
+            Message::ImportFailed { path, reason } => tr!(
+                f,
+                "'{}' आयात गर्न सकिएन: {}",
+                "could not import '{}': {}",
+                path,
+                reason
+            ),
+            Message::ImportCycle(cycle) => {
+                tr!(f, "मोड्युलहरूले एक-अर्कालाई आयात गर्छन्: {}", "import cycle: {}", cycle)
+            }
+            Message::NoSuchModuleMember { module, name } => tr!(
+                f,
+                "मोड्युल '{}' मा '{}' छैन",
+                "module '{}' has no member '{}'",
+                module,
+                name
+            ),
+            Message::UndefinedVariable { name } => tr!(
+                f,
+                "अपरिभाषित भेरिएबल '{}' को प्रयोग।\nनोट: '{}' कहिल्यै घोषणा गरिएको छैन।",
+                "Use of undefined variable '{}'.\nNote: '{}' was never declared.",
+                name,
+                name
+            ),
+            Message::UndefinedButDeclared {
+                name,
+                decl_line,
+                decl_col,
+            } => tr!(
+                f,
+                "अपरिभाषित भेरिएबल '{}' को प्रयोग।\nनोट: '{}' रेखा {}, स्तम्भ {} मा घोषणा गरिएको थियो, तर कहिल्यै मान दिइएन।",
+                "Use of undefined variable '{}'.\nNote: '{}' was previously declared at line {}, column {}, but was never defined.",
+                name,
+                name,
+                decl_line,
+                decl_col
+            ),
+            Message::AssignToUndeclared { name } => tr!(
+                f,
+                "घोषणा नगरिएको भेरिएबल '{}' मा मान राख्ने प्रयास",
+                "attempting to assign to undeclared variable '{}'",
+                name
+            ),
+            Message::InitReturnsNonNil(ty) => tr!(
+                f,
+                "TypeError: 'सुरु' ले आलु (nil) मात्र फर्काउनु पर्छ, {} होइन",
+                "TypeError: 'सुरु' (init) should only return nil (perhaps implicitly), not {}",
+                TypeName(*ty)
+            ),
+            Message::NoSuchAttribute { class_name, attr } => tr!(
+                f,
+                "AttributeError: '{}' वस्तुमा '{}' गुण छैन।",
+                "AttributeError: '{}' instance has no '{}' attribute.",
+                class_name,
+                attr
+            ),
+            Message::NoAttributes(ty) => tr!(
+                f,
+                "वस्तु (instance) मा मात्र गुणहरू हुन्छन्। {} भेटियो।",
+                "Only instances have attributes. Found {}.",
+                TypeName(*ty)
+            ),
+            Message::ClassInheritsFromItself { name } => tr!(
+                f,
+                "वर्ग '{}' आफैँबाट inherit गर्न सक्दैन",
+                "class '{}' cannot inherit from itself",
+                name
+            ),
+            Message::SuperclassNotClass(ty) => tr!(
+                f,
+                "अभिभावक (superclass) वर्ग मात्र हुन सक्छ। {} भेटियो।",
+                "Only classes should appear as superclasses. Found {}.",
+                TypeName(*ty)
+            ),
+            Message::NoSuperMethod { name } => tr!(
+                f,
+                "कुनै अभिभावक वर्गमा '{}' मेथड छैन",
+                "no superclass has method '{}'",
+                name
+            ),
+            Message::SuperOutsideMethod => tr!(
+                f,
+                "'सुपर' मेथड भित्र मात्र प्रयोग गर्न मिल्छ",
+                "'सुपर' (super) expression not enclosed in a method definition"
+            ),
+            Message::NotCallable { ty } => tr!(
+                f,
+                "{} कल गर्न मिल्दैन",
+                "value of type {} is not callable",
+                TypeName(*ty)
+            ),
+            Message::ArityMismatch { arity, num_args } => tr!(
+                f,
+                "अवैध कल: {} आर्गुमेन्ट चाहिन्छ, तर {} दिइयो",
+                "Invalid call: callee has arity {}, but was called with {} arguments",
+                arity,
+                num_args
+            ),
+            Message::DivisionByZero => tr!(f, "शून्यले भाग", "division by zero"),
+            Message::InvalidBinaryOperands { op, lhs, rhs } => tr!(
+                f,
+                "{} अपरेटरमा {} र {} प्रयोग गर्न मिल्दैन",
+                "invalid operands in binary operator {} of type {} and {}",
+                BinaryOpName(*op),
+                TypeName(*lhs),
+                TypeName(*rhs)
+            ),
+            Message::InvalidUnaryOperand { op, ty } => tr!(
+                f,
+                "{} अपरेटर {} मा प्रयोग गर्न मिल्दैन",
+                "invalid application of unary op {} to object of type {}",
+                UnaryOpName(*op),
+                TypeName(*ty)
+            ),
+            Message::NotSubscriptable { ty } => tr!(
+                f,
+                "{} मा सूचकाङ्क (index) प्रयोग गर्न मिल्दैन",
+                "value of type {} can't be indexed",
+                TypeName(*ty)
+            ),
+            Message::IndexNotNumber { ty } => tr!(
+                f,
+                "सूचकाङ्क (index) सङ्ख्या हुनुपर्छ, {} भेटियो",
+                "index must be a Number, found {}",
+                TypeName(*ty)
+            ),
+            Message::IndexNotInteger { index } => tr!(
+                f,
+                "सूचकाङ्क (index) पूर्णाङ्क हुनुपर्छ, {} भेटियो",
+                "index must be an integer, found {}",
+                index
+            ),
+            Message::IndexOutOfRange { index, len } => tr!(
+                f,
+                "सूचकाङ्क {} लम्बाइ {} भएको सूचीको दायराभन्दा बाहिर छ",
+                "index {} out of range for list of length {}",
+                index,
+                len
+            ),
+            Message::InvalidKey { ty } => tr!(
+                f,
+                "{} शब्दकोशको कुञ्जी (key) हुन सक्दैन",
+                "{} cannot be used as a dict key",
+                TypeName(*ty)
+            ),
+            Message::KeyNotFound { key } => tr!(
+                f,
+                "कुञ्जी {} शब्दकोशमा छैन",
+                "key {} not found in dict",
+                key
+            ),
+            Message::NoLen(ty) => tr!(
+                f,
+                "{} को लम्बाइ हुँदैन।",
+                "Object of type {} has no len.",
+                TypeName(*ty)
+            ),
+            Message::InvalidArgument {
+                function,
+                position,
+                ty,
+            } => tr!(
+                f,
+                "'{}' को आर्गुमेन्ट नं. {} मा {} दिन मिल्दैन।",
+                "invalid argument {1} of type {2} to '{0}'.",
+                function,
+                position,
+                TypeName(*ty)
+            ),
+            Message::ArgumentNotCallable {
+                function,
+                position,
+                ty,
+            } => tr!(
+                f,
+                "'{}' को आर्गुमेन्ट नं. {} कल गर्न मिल्ने हुनुपर्छ, {} भेटियो।",
+                "argument {1} to '{0}' must be callable. Found {2}.",
+                function,
+                position,
+                TypeName(*ty)
+            ),
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use crate::expr;
+use crate::messages;
 use crate::scanner;
 
 use std::fmt;
@@ -7,25 +8,23 @@ use std::fmt;
 struct Parser {
     tokens: Vec<scanner::Token>,
     current: usize,
-    in_fundec: bool, // in rust, booleans default to false: https://doc.rust-lang.org/std/primitive.bool.html#impl-Default
+    block_depth: usize,
+    // loops enclosing the current statement within the current function
+    loop_depth: usize,
+    errors: Vec<Error>,
 }
 
 pub enum Error {
     UnexpectedToken(scanner::Token),
     TokenMismatch {
-        expected: scanner::TokenType,
+        expected: messages::Expected,
         found: scanner::Token,
-        maybe_on_err_string: Option<String>,
     },
     MaxParamsExceeded {
         kind: FunctionKind,
         line: usize,
         col: i64,
     },
-    ReturnNotInFun {
-        line: usize,
-        col: i64,
-    },
     InvalidAssignment {
         line: usize,
         col: i64,
@@ -49,118 +48,157 @@ pub enum Error {
         line: usize,
         col: i64,
     },
+    LoopControlOutsideLoop(scanner::Token),
 }
 
 impl fmt::Debug for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match &self {
-            Error::UnexpectedToken(tok) => write!(
-                f,
-                "Unexpected token {:?} at line={},col={}",
-                tok.ty, tok.line, tok.col
-            ),
+        let message = match self {
+            Error::UnexpectedToken(tok) => messages::Message::UnexpectedToken(tok.ty),
             Error::TokenMismatch {
-                maybe_on_err_string,
-                expected,
-                found,
-            } => {
-                write!(
-                    f,
-                    " expected टोकन {:?} तर भेट्टियो {:?}.  रेखा={},स्नम्भ={}",
-                    expected, found.ty, found.line, found.col
-                )?;
-                if let Some(on_err_string) = maybe_on_err_string {
-                    write!(f, ": {}", on_err_string)?;
-                }
-                fmt::Result::Ok(())
+                expected, found, ..
+            } => messages::Message::TokenMismatch {
+                expected: *expected,
+                found: found.ty,
+            },
+            Error::MaxParamsExceeded { kind, .. } => messages::Message::MaxParamsExceeded(*kind),
+            Error::InvalidAssignment { .. } => messages::Message::InvalidAssignment,
+            Error::TooManyArguments { .. } => messages::Message::TooManyArguments,
+            Error::ExpectedExpression { token_type, .. } => {
+                messages::Message::ExpectedExpression(*token_type)
             }
-            Error::MaxParamsExceeded { kind, line, col } => write!(
-                f,
-                "{:?} घोषणा(function) मा २५५ भन्दा बढी प्यारामिटरहरू हुन सक्दैन। रेखा={}, स्तम्भ={}",
-                kind, line, col
-            ),
-            Error::ReturnNotInFun { line, col } => write!(
-                f,
-                "रिटर्न स्टेटमेन्ट लाइन={}, स्नम्भ={} मा कार्य (function) मा संलग्न छैन",
-                line, col
-            ),
-            Error::InvalidAssignment { line, col } => {
-                write!(f, "लाइन={}, स्नम्भ={} मा अवैध असाइनमेन्ट लक्ष्य", line, col)
+            Error::InvalidTokenInUnaryOp { token_type, .. } => {
+                messages::Message::InvalidTokenInUnaryOp(*token_type)
             }
-            Error::TooManyArguments { line, col } => write!(
-                f,
-                "घोषणा(function) मा २५५ भन्दा बढी प्यारामिटरहरू हुन सक्दैन। रेखा={}, स्तम्भ={}",
-                line, col
-            ),
-            Error::ExpectedExpression {
-                token_type,
-                line,
-                col,
-            } => write!(
-                f,
-                "expected अभिव्यक्ति(expected expression), तर लाइन={}, col={} मा टोकन(Token) {:?} भेटियो",
-                 line, col, token_type
-            ),
-            Error::InvalidTokenInUnaryOp {
-                token_type,
-                line,
-                col,
-            } => write!(
-                f,
-                "अवैध टोकन in unary op {:?} at लाइन={}, स्नम्भ={}",
-                token_type, line, col
-            ),
-            Error::InvalidTokenInBinaryOp {
-                token_type,
-                line,
-                col,
-            } => write!(
-                f,
-                "अवैध टोकन in binary op {:?} at लाइन={}, स्नम्भ={}",
-                token_type, line, col
-            ),
-        }
+            Error::InvalidTokenInBinaryOp { token_type, .. } => {
+                messages::Message::InvalidTokenInBinaryOp(*token_type)
+            }
+            Error::LoopControlOutsideLoop(tok) => messages::Message::LoopControlOutsideLoop(tok.ty),
+        };
+        write!(f, "{}", message)
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Copy, Clone)]
 pub enum FunctionKind {
     Function,
     Method,
+    Lambda,
+}
+
+/// The text of the `///` comments before `tok`, one line each.
+fn doc_comment(tok: &scanner::Token) -> Option<String> {
+    let lines: Vec<&str> = tok
+        .leading_trivia()
+        .iter()
+        .filter_map(|trivia| match trivia {
+            scanner::Trivia::DocComment(text) => {
+                let text = text.trim_start_matches('/');
+                Some(text.strip_prefix(' ').unwrap_or(text).trim_end())
+            }
+            _ => None,
+        })
+        .collect();
+
+    if lines.is_empty() {
+        None
+    } else {
+        Some(lines.join("\n"))
+    }
 }
 
 pub fn parse(
     tokens: Vec<scanner::Token>,
-) -> Result<Vec<expr::Stmt>, Error> {
+) -> Result<Vec<expr::Stmt>, Vec<Error>> {
     let mut p = Parser {
         tokens,
         ..Default::default()
     };
-    let stmts_or_err = p.parse();
+    let stmts = p.parse();
 
-    match stmts_or_err {
-        Ok(stmts_or_err) => {
-            if !p.is_at_end() {
-                let tok = &p.tokens[p.current];
-                Err(Error::UnexpectedToken(tok.clone()))
-            } else {
-                Ok(stmts_or_err)
-            }
-        }
-        Err(err) => Err(err),
+    if !p.is_at_end() {
+        let tok = &p.tokens[p.current];
+        p.errors.push(Error::UnexpectedToken(tok.clone()));
+    }
+
+    if p.errors.is_empty() {
+        Ok(stmts)
+    } else {
+        Err(p.errors)
     }
 }
 
 impl Parser {
-    pub fn parse(&mut self) -> Result<Vec<expr::Stmt>, Error> {
+    pub fn parse(&mut self) -> Vec<expr::Stmt> {
         let mut statements = Vec::new();
 
         while !self.is_at_end() {
-            let stmt = self.declaration()?;
-            statements.push(stmt);
+            if let Some(stmt) = self.declaration_or_synchronize() {
+                statements.push(stmt);
+            }
+        }
+
+        statements
+    }
+
+    /// Parses a declaration, recording any error and skipping ahead to the next statement
+    /// boundary so that parsing can continue and report further errors.
+    fn declaration_or_synchronize(&mut self) -> Option<expr::Stmt> {
+        match self.declaration() {
+            Ok(stmt) => Some(stmt),
+            Err(err) => {
+                self.errors.push(err);
+                self.synchronize();
+                None
+            }
+        }
+    }
+
+    fn synchronize(&mut self) {
+        // braces opened while skipping; their contents are skipped as a whole
+        let mut nesting = 0;
+
+        // the `}` that closes the enclosing block is left for that block to consume
+        if self.check(scanner::TokenType::RightBrace) && self.block_depth > 0 {
+            return;
         }
+        self.advance();
+
+        loop {
+            match self.previous().ty {
+                scanner::TokenType::LeftBrace => nesting += 1,
+                scanner::TokenType::RightBrace if nesting > 0 => nesting -= 1,
+                scanner::TokenType::Semicolon if nesting == 0 => return,
+                _ => {}
+            }
 
-        Ok(statements)
+            if self.is_at_end() {
+                return;
+            }
+
+            if nesting == 0 {
+                match self.peek().ty {
+                    scanner::TokenType::Class
+                    | scanner::TokenType::Fun
+                    | scanner::TokenType::Var
+                    | scanner::TokenType::For
+                    | scanner::TokenType::If
+                    | scanner::TokenType::While
+                    | scanner::TokenType::Print
+                    | scanner::TokenType::Return
+                    | scanner::TokenType::Break
+                    | scanner::TokenType::Continue
+                    | scanner::TokenType::Throw
+                    | scanner::TokenType::Try
+                    | scanner::TokenType::Import => return,
+                    // leave the closing brace for the enclosing block
+                    scanner::TokenType::RightBrace if self.block_depth > 0 => return,
+                    _ => {}
+                }
+            }
+
+            self.advance();
+        }
     }
 
     fn declaration(&mut self) -> Result<expr::Stmt, Error> {
@@ -168,7 +206,8 @@ impl Parser {
             return self.var_decl();
         }
 
-        if self.matches(scanner::TokenType::Fun) {
+        if self.check(scanner::TokenType::Fun) && self.check_next(scanner::TokenType::Identifier) {
+            self.advance();
             return Ok(expr::Stmt::FunDecl(self.fun_decl(FunctionKind::Function)?));
         }
 
@@ -176,12 +215,17 @@ impl Parser {
             return self.class_decl();
         }
 
+        if self.matches(scanner::TokenType::Import) {
+            return self.import_decl();
+        }
+
         self.statement()
     }
 
     fn class_decl(&mut self) -> Result<expr::Stmt, Error> {
+        let doc = doc_comment(self.previous());
         let name_tok = self
-            .consume(scanner::TokenType::Identifier, "expected class name")?
+            .consume(scanner::TokenType::Identifier, messages::Expected::ClassName)?
             .clone();
 
         let class_symbol = expr::Symbol {
@@ -192,7 +236,7 @@ impl Parser {
 
         let superclass_maybe = if self.matches(scanner::TokenType::Less) {
             let superclass_tok =
-                self.consume(scanner::TokenType::Identifier, "expected class  नाम (name) .")?;
+                self.consume(scanner::TokenType::Identifier, messages::Expected::SuperclassName)?;
             Some(expr::Symbol {
                 name: superclass_tok.lexeme.iter().collect(),
                 line: superclass_tok.line,
@@ -202,7 +246,7 @@ impl Parser {
             None
         };
 
-        self.consume(scanner::TokenType::LeftBrace, "expected {  पछि  class name")?;
+        self.consume(scanner::TokenType::LeftBrace, messages::Expected::LeftBraceAfterClassName)?;
 
         let mut methods = Vec::new();
         while !self.check(scanner::TokenType::RightBrace) && !self.is_at_end() {
@@ -212,21 +256,57 @@ impl Parser {
 
         self.consume(
             scanner::TokenType::RightBrace,
-            "expected }  पछि  class body",
+            messages::Expected::RightBraceAfterClassBody,
         )?;
 
         Ok(expr::Stmt::ClassDecl(expr::ClassDecl {
+            doc,
             name: class_symbol,
             superclass: superclass_maybe,
             methods,
         }))
     }
 
+    fn import_decl(&mut self) -> Result<expr::Stmt, Error> {
+        let name_tok = self
+            .consume(scanner::TokenType::Identifier, messages::Expected::ModuleName)?
+            .clone();
+        self.consume(scanner::TokenType::Equal, messages::Expected::EqualAfterModuleName)?;
+        let path_tok = self
+            .consume(scanner::TokenType::String, messages::Expected::ModulePath)?
+            .clone();
+        self.consume(scanner::TokenType::Semicolon, messages::Expected::SemicolonAfterImport)?;
+
+        let path = match path_tok.literal {
+            Some(scanner::Literal::Str(path)) => path,
+            _ => panic!("internal error in parser: when parsing string, found no literal"),
+        };
+
+        Ok(expr::Stmt::Import {
+            name: expr::Symbol {
+                name: name_tok.lexeme.iter().collect(),
+                line: name_tok.line,
+                col: name_tok.col,
+            },
+            path,
+            source_location: expr::SourceLocation {
+                line: path_tok.line,
+                col: path_tok.col,
+            },
+            path_len: path_tok.lexeme.len(),
+        })
+    }
+
     fn fun_decl(&mut self, kind: FunctionKind) -> Result<expr::FunDecl, Error> {
+        // a method's docs are before its name, a function's before its keyword
+        let doc = match kind {
+            FunctionKind::Method => doc_comment(self.peek()),
+            _ => doc_comment(self.previous()),
+        };
         let name_tok = self
             .consume(
                 scanner::TokenType::Identifier,
-                format!("expected {:?} name", kind).as_ref(),
+                messages::Expected::FunctionName(kind),
             )?
             .clone();
 
@@ -239,6 +319,7 @@ impl Parser {
         let (parameters, body) = self.params_and_body(kind)?;
 
         Ok(expr::FunDecl {
+            doc,
             name: fun_symbol,
             params: parameters,
             body,
@@ -251,7 +332,7 @@ impl Parser {
     ) -> Result<(Vec<expr::Symbol>, Vec<expr::Stmt>), Error> {
         self.consume(
             scanner::TokenType::LeftParen,
-            format!("expected (  पछि  {:?} name", kind).as_ref(),
+            messages::Expected::LeftParenAfterFunctionName(kind),
         )?;
 
         let mut parameters = Vec::new();
@@ -268,7 +349,7 @@ impl Parser {
                 }
 
                 let tok = self
-                    .consume(scanner::TokenType::Identifier, "expected  प्यारामीटर(parameter) name")?
+                    .consume(scanner::TokenType::Identifier, messages::Expected::ParameterName)?
                     .clone();
 
                 parameters.push(expr::Symbol {
@@ -286,23 +367,23 @@ impl Parser {
 
         self.consume(
             scanner::TokenType::RightParen,
-            "parameter",
+            messages::Expected::RightParenAfterParameters,
         )?;
         self.consume(
             scanner::TokenType::LeftBrace,
-            "expected { before function body",
+            messages::Expected::LeftBraceBeforeFunctionBody,
         )?;
-        let saved_is_in_fundec = self.in_fundec;
-        self.in_fundec = true;
-        let body = self.block()?;
-        self.in_fundec = saved_is_in_fundec;
+        // a loop around the function doesn't let its body break out of it
+        let enclosing_loop_depth = std::mem::replace(&mut self.loop_depth, 0);
+        let body = self.block();
+        self.loop_depth = enclosing_loop_depth;
 
-        Ok((parameters, body))
+        Ok((parameters, body?))
     }
 
     fn var_decl(&mut self) -> Result<expr::Stmt, Error> {
         let name_token = self
-            .consume(scanner::TokenType::Identifier, "expected  भेरिएबल (variable)  name")?
+            .consume(scanner::TokenType::Identifier, messages::Expected::VariableName)?
             .clone();
 
         let maybe_initializer = if self.matches(scanner::TokenType::Equal) {
@@ -313,7 +394,7 @@ impl Parser {
 
         self.consume(
             scanner::TokenType::Semicolon,
-            "expected ;  पछि   भेरिएबल (variable)  declaration",
+            messages::Expected::SemicolonAfterVarDecl,
         )?;
 
         Ok(expr::Stmt::VarDecl(
@@ -351,19 +432,24 @@ impl Parser {
             return self.return_statement();
         }
 
+        if self.matches(scanner::TokenType::Break) || self.matches(scanner::TokenType::Continue) {
+            return self.loop_control_statement();
+        }
+
+        if self.matches(scanner::TokenType::Throw) {
+            return self.throw_statement();
+        }
+
+        if self.matches(scanner::TokenType::Try) {
+            return self.try_statement();
+        }
+
         self.expression_statement()
     }
 
     fn return_statement(&mut self) -> Result<expr::Stmt, Error> {
         let prev_tok = self.previous().clone();
 
-        if !self.in_fundec {
-            return Err(Error::ReturnNotInFun {
-                line: prev_tok.line,
-                col: prev_tok.col,
-            });
-        }
-
         let maybe_retval = if !self.matches(scanner::TokenType::Semicolon) {
             Some(self.expression()?)
         } else {
@@ -373,7 +459,7 @@ impl Parser {
         if maybe_retval.is_some() {
             self.consume(
                 scanner::TokenType::Semicolon,
-                "expected ;  पछि   फर्कने (return)   भ्यालु (value)",
+                messages::Expected::SemicolonAfterReturnValue,
             )?;
         }
 
@@ -386,8 +472,90 @@ impl Parser {
         ))
     }
 
+    fn loop_control_statement(&mut self) -> Result<expr::Stmt, Error> {
+        let keyword = self.previous().clone();
+
+        if self.loop_depth == 0 {
+            return Err(Error::LoopControlOutsideLoop(keyword));
+        }
+
+        self.consume(
+            scanner::TokenType::Semicolon,
+            messages::Expected::SemicolonAfterLoopControl(keyword.ty),
+        )?;
+
+        Ok(match keyword.ty {
+            scanner::TokenType::Break => expr::Stmt::Break,
+            _ => expr::Stmt::Continue,
+        })
+    }
+
+    fn throw_statement(&mut self) -> Result<expr::Stmt, Error> {
+        let throw_tok = self.previous().clone();
+        let value = self.expression()?;
+        self.consume(
+            scanner::TokenType::Semicolon,
+            messages::Expected::SemicolonAfterThrowValue,
+        )?;
+
+        Ok(expr::Stmt::Throw(
+            expr::SourceLocation {
+                line: throw_tok.line,
+                col: throw_tok.col,
+            },
+            value,
+        ))
+    }
+
+    fn try_statement(&mut self) -> Result<expr::Stmt, Error> {
+        self.consume(scanner::TokenType::LeftBrace, messages::Expected::LeftBraceAfterTry)?;
+        let body = self.block()?;
+
+        let catch = if self.matches(scanner::TokenType::Catch) {
+            self.consume(scanner::TokenType::LeftParen, messages::Expected::LeftParenAfterCatch)?;
+            let name_tok = self
+                .consume(scanner::TokenType::Identifier, messages::Expected::CatchVariableName)?
+                .clone();
+            self.consume(
+                scanner::TokenType::RightParen,
+                messages::Expected::RightParenAfterCatchVariable,
+            )?;
+            self.consume(scanner::TokenType::LeftBrace, messages::Expected::LeftBraceAfterCatch)?;
+            Some(expr::Catch {
+                name: expr::Symbol {
+                    name: name_tok.lexeme.iter().collect(),
+                    line: name_tok.line,
+                    col: name_tok.col,
+                },
+                body: self.block()?,
+            })
+        } else {
+            None
+        };
+
+        let finally = if self.matches(scanner::TokenType::Finally) {
+            self.consume(scanner::TokenType::LeftBrace, messages::Expected::LeftBraceAfterFinally)?;
+            Some(self.block()?)
+        } else {
+            None
+        };
+
+        if catch.is_none() && finally.is_none() {
+            return Err(Error::TokenMismatch {
+                expected: messages::Expected::CatchOrFinallyAfterTry,
+                found: self.peek().clone(),
+            });
+        }
+
+        Ok(expr::Stmt::Try {
+            body,
+            catch,
+            finally,
+        })
+    }
+
     fn for_statement(&mut self) -> Result<expr::Stmt, Error> {
-        self.consume(scanner::TokenType::LeftParen, "expected (  पछि  for.")?;
+        self.consume(scanner::TokenType::LeftParen, messages::Expected::LeftParenAfterFor)?;
 
         let mut maybe_initializer: Option<expr::Stmt> = None;
         if self.matches(scanner::TokenType::Semicolon) {
@@ -406,7 +574,7 @@ impl Parser {
 
         self.consume(
             scanner::TokenType::Semicolon,
-            "expected ;  पछि  loop condition",
+            messages::Expected::SemicolonAfterLoopCondition,
         )?;
 
         let maybe_increment = if !self.check(scanner::TokenType::RightParen) {
@@ -417,20 +585,16 @@ impl Parser {
 
         self.consume(
             scanner::TokenType::RightParen,
-            "expected )  पछि  for clauses",
+            messages::Expected::RightParenAfterForClauses,
         )?;
 
-        let mut body = self.statement()?;
-
-        if let Some(increment) = maybe_increment {
-            body = expr::Stmt::Block(vec![body, expr::Stmt::Expr(increment)])
-        }
+        let mut body = self.loop_body()?;
 
         let condition = match maybe_condition {
             Some(cond) => cond,
             None => expr::Expr::Literal(expr::Literal::True),
         };
-        body = expr::Stmt::While(condition, Box::new(body));
+        body = expr::Stmt::While(condition, Box::new(body), maybe_increment);
 
         if let Some(initializer) = maybe_initializer {
             body = expr::Stmt::Block(vec![initializer, body])
@@ -441,22 +605,29 @@ impl Parser {
     }
 
     fn while_statement(&mut self) -> Result<expr::Stmt, Error> {
-        self.consume(scanner::TokenType::LeftParen, "expected (  पछि  while")?;
+        self.consume(scanner::TokenType::LeftParen, messages::Expected::LeftParenAfterWhile)?;
         let cond = self.expression()?;
         self.consume(
             scanner::TokenType::RightParen,
-            "expected )  पछि  while condition",
+            messages::Expected::RightParenAfterWhileCondition,
         )?;
-        let body = Box::new(self.statement()?);
-        Ok(expr::Stmt::While(cond, body))
+        let body = Box::new(self.loop_body()?);
+        Ok(expr::Stmt::While(cond, body, None))
+    }
+
+    fn loop_body(&mut self) -> Result<expr::Stmt, Error> {
+        self.loop_depth += 1;
+        let res = self.statement();
+        self.loop_depth -= 1;
+        res
     }
 
     fn if_statement(&mut self) -> Result<expr::Stmt, Error> {
-        self.consume(scanner::TokenType::LeftParen, "expected (  पछि  if.")?;
+        self.consume(scanner::TokenType::LeftParen, messages::Expected::LeftParenAfterIf)?;
         let cond = self.expression()?;
         self.consume(
             scanner::TokenType::RightParen,
-            "expected )  पछि  if condition.",
+            messages::Expected::RightParenAfterIfCondition,
         )?;
         let then_branch = Box::new(self.statement()?);
         let maybe_else_branch = if self.matches(scanner::TokenType::Else) {
@@ -471,24 +642,28 @@ impl Parser {
     fn block(&mut self) -> Result<Vec<expr::Stmt>, Error> {
         let mut stmts = Vec::new();
 
+        self.block_depth += 1;
         while !self.check(scanner::TokenType::RightBrace) && !self.is_at_end() {
-            stmts.push(self.declaration()?)
+            if let Some(stmt) = self.declaration_or_synchronize() {
+                stmts.push(stmt);
+            }
         }
+        self.block_depth -= 1;
 
-        self.consume(scanner::TokenType::RightBrace, "expected }  पछि  block.")?;
+        self.consume(scanner::TokenType::RightBrace, messages::Expected::RightBraceAfterBlock)?;
 
         Ok(stmts)
     }
 
     fn print_statement(&mut self) -> Result<expr::Stmt, Error> {
         let expr = self.expression()?;
-        self.consume(scanner::TokenType::Semicolon, "expected ;  पछि  भ्यालु (value)")?;
+        self.consume(scanner::TokenType::Semicolon, messages::Expected::SemicolonAfterValue)?;
         Ok(expr::Stmt::Print(expr))
     }
 
     fn expression_statement(&mut self) -> Result<expr::Stmt, Error> {
         let expr = self.expression()?;
-        self.consume(scanner::TokenType::Semicolon, "expected ;  पछि  भ्यालु (value)")?;
+        self.consume(scanner::TokenType::Semicolon, messages::Expected::SemicolonAfterExpression)?;
         Ok(expr::Stmt::Expr(expr))
     }
 
@@ -507,6 +682,18 @@ impl Parser {
                 return Ok(expr::Expr::Assign(sym.clone(), Box::new(new_value)));
             } else if let expr::Expr::Get(e, attr) = expr {
                 return Ok(expr::Expr::Set(e, attr, Box::new(new_value)));
+            } else if let expr::Expr::Subscript {
+                value,
+                slice,
+                source_location,
+            } = expr
+            {
+                return Ok(expr::Expr::SetItem {
+                    lhs: value,
+                    slice,
+                    rhs: Box::new(new_value),
+                    source_location,
+                });
             } else {
                 return Err(Error::InvalidAssignment {
                     line: equals.line,
@@ -515,9 +702,48 @@ impl Parser {
             }
         }
 
+        if self.match_one_of(vec![
+            scanner::TokenType::PlusEqual,
+            scanner::TokenType::MinusEqual,
+            scanner::TokenType::StarEqual,
+            scanner::TokenType::SlashEqual,
+        ]) {
+            let operator_token = self.previous().clone();
+            let value = self.assignment()?;
+
+            return match expr {
+                expr::Expr::Variable(_) | expr::Expr::Get(..) | expr::Expr::Subscript { .. } => {
+                    Ok(expr::Expr::CompoundAssign {
+                        target: Box::new(expr),
+                        op: Parser::compound_assign_op(&operator_token),
+                        value: Box::new(value),
+                    })
+                }
+                _ => Err(Error::InvalidAssignment {
+                    line: operator_token.line,
+                    col: operator_token.col,
+                }),
+            };
+        }
+
         Ok(expr)
     }
 
+    fn compound_assign_op(tok: &scanner::Token) -> expr::BinaryOp {
+        let ty = match tok.ty {
+            scanner::TokenType::PlusEqual => expr::BinaryOpTy::Plus,
+            scanner::TokenType::MinusEqual => expr::BinaryOpTy::Minus,
+            scanner::TokenType::StarEqual => expr::BinaryOpTy::Star,
+            scanner::TokenType::SlashEqual => expr::BinaryOpTy::Slash,
+            _ => panic!("Internal parser error! {:?} is not a compound assignment", tok.ty),
+        };
+        expr::BinaryOp {
+            ty,
+            line: tok.line,
+            col: tok.col,
+        }
+    }
+
     fn or(&mut self) -> Result<expr::Expr, Error> {
         let mut expr = self.and()?;
 
@@ -586,7 +812,11 @@ impl Parser {
     fn multiplication(&mut self) -> Result<expr::Expr, Error> {
         let mut expr = self.unary()?;
 
-        while self.match_one_of(vec![scanner::TokenType::Slash, scanner::TokenType::Star]) {
+        while self.match_one_of(vec![
+            scanner::TokenType::Slash,
+            scanner::TokenType::Star,
+            scanner::TokenType::Percent,
+        ]) {
             let operator_token = self.previous().clone();
             let right = Box::new(self.unary()?);
             let binop_maybe = Parser::op_token_to_binop(&operator_token);
@@ -613,7 +843,20 @@ impl Parser {
                 Err(err) => Err(err),
             };
         }
-        self.call()
+        self.exponent()
+    }
+
+    fn exponent(&mut self) -> Result<expr::Expr, Error> {
+        let expr = self.call()?;
+
+        if self.matches(scanner::TokenType::StarStar) {
+            let operator_token = self.previous().clone();
+            // right associative, and the exponent may itself be negated: 2 ** -1
+            let right = Box::new(self.unary()?);
+            let binop = Parser::op_token_to_binop(&operator_token)?;
+            return Ok(expr::Expr::Binary(Box::new(expr), binop, right));
+        }
+        Ok(expr)
     }
 
     fn call(&mut self) -> Result<expr::Expr, Error> {
@@ -626,7 +869,7 @@ impl Parser {
                 let name_tok = self
                     .consume(
                         scanner::TokenType::Identifier,
-                        "expected  गुड (property)   नाम (name)   पछि  '.'.",
+                        messages::Expected::PropertyNameAfterDot,
                     )?
                     .clone();
                 expr = expr::Expr::Get(
@@ -637,10 +880,53 @@ impl Parser {
                         col: name_tok.col,
                     },
                 );
+            } else if self.matches(scanner::TokenType::LeftBracket) {
+                let slice_expr = self.expression()?;
+                let token = self.consume(
+                    scanner::TokenType::RightBracket,
+                    messages::Expected::RightBracketAfterSubscript,
+                )?;
+                expr = expr::Expr::Subscript {
+                    value: Box::new(expr),
+                    slice: Box::new(slice_expr),
+                    source_location: expr::SourceLocation {
+                        line: token.line,
+                        col: token.col,
+                    },
+                };
             } else {
                 break;
             }
         }
+
+        if self.match_one_of(vec![
+            scanner::TokenType::PlusPlus,
+            scanner::TokenType::MinusMinus,
+        ]) {
+            let operator_token = self.previous().clone();
+            let ty = match operator_token.ty {
+                scanner::TokenType::PlusPlus => expr::BinaryOpTy::Plus,
+                _ => expr::BinaryOpTy::Minus,
+            };
+
+            return match expr {
+                expr::Expr::Variable(_) | expr::Expr::Get(..) | expr::Expr::Subscript { .. } => {
+                    Ok(expr::Expr::Increment {
+                        target: Box::new(expr),
+                        op: expr::BinaryOp {
+                            ty,
+                            line: operator_token.line,
+                            col: operator_token.col,
+                        },
+                    })
+                }
+                _ => Err(Error::InvalidAssignment {
+                    line: operator_token.line,
+                    col: operator_token.col,
+                }),
+            };
+        }
+
         Ok(expr)
     }
 
@@ -665,7 +951,7 @@ impl Parser {
 
         let token = self.consume(
             scanner::TokenType::RightParen,
-            "expected )  पछि  arguments.",
+            messages::Expected::RightParenAfterArguments,
         )?;
 
         Ok(expr::Expr::Call(
@@ -678,6 +964,34 @@ impl Parser {
         ))
     }
 
+    /// The rest of an interpolated string, after its first `Interpolation` token.
+    fn interpolation(&mut self) -> Result<expr::Expr, Error> {
+        let mut parts = Vec::new();
+
+        loop {
+            match &self.previous().literal {
+                Some(scanner::Literal::Str(s)) if s.is_empty() => {}
+                Some(scanner::Literal::Str(s)) => {
+                    parts.push(expr::Expr::Literal(expr::Literal::String(s.clone())))
+                }
+                _ => panic!("internal error in parser: when parsing string, found no literal"),
+            }
+
+            if self.previous().ty == scanner::TokenType::InterpolationEnd {
+                return Ok(expr::Expr::Interpolation(parts));
+            }
+
+            parts.push(self.expression()?);
+
+            if !self.matches(scanner::TokenType::InterpolationMiddle) {
+                self.consume(
+                    scanner::TokenType::InterpolationEnd,
+                    messages::Expected::RightBraceAfterInterpolation,
+                )?;
+            }
+        }
+    }
+
     fn primary(&mut self) -> Result<expr::Expr, Error> {
         if self.matches(scanner::TokenType::False) {
             return Ok(expr::Expr::Literal(expr::Literal::False));
@@ -690,10 +1004,10 @@ impl Parser {
         }
         if self.matches(scanner::TokenType::Super) {
             let super_tok = self.previous().clone();
-            self.consume(scanner::TokenType::Dot, "expected '.'  पछि  'super'.")?;
+            self.consume(scanner::TokenType::Dot, messages::Expected::DotAfterSuper)?;
             let method_tok = self.consume(
                 scanner::TokenType::Identifier,
-                "expected superclass method  नाम (name) .",
+                messages::Expected::SuperclassMethodName,
             )?;
             return Ok(expr::Expr::Super(
                 expr::SourceLocation {
@@ -731,6 +1045,9 @@ impl Parser {
                 None => panic!("internal error in parser: when parsing string, found no literal"),
             }
         }
+        if self.matches(scanner::TokenType::Interpolation) {
+            return self.interpolation();
+        }
         if self.matches(scanner::TokenType::This) {
             let prev = self.previous();
             return Ok(expr::Expr::This(expr::SourceLocation {
@@ -760,30 +1077,107 @@ impl Parser {
             let expr = Box::new(self.expression()?);
             self.consume(
                 scanner::TokenType::RightParen,
-                "expected ')'  पछि  expression.",
+                messages::Expected::RightParenAfterExpression,
             )?;
             return Ok(expr::Expr::Grouping(expr));
         }
+        if self.matches(scanner::TokenType::Fun) {
+            let fun_tok = self.previous().clone();
+            let (params, body) = self.params_and_body(FunctionKind::Lambda)?;
+            return Ok(expr::Expr::Lambda(expr::LambdaDecl {
+                params,
+                body,
+                source_location: expr::SourceLocation {
+                    line: fun_tok.line,
+                    col: fun_tok.col,
+                },
+            }));
+        }
+        if self.matches(scanner::TokenType::LeftBracket) {
+            let mut list_elements = Vec::new();
+
+            if !self.check(scanner::TokenType::RightBracket) {
+                loop {
+                    list_elements.push(self.expression()?);
+                    if !self.matches(scanner::TokenType::Comma) {
+                        break;
+                    }
+                }
+            }
+
+            self.consume(
+                scanner::TokenType::RightBracket,
+                messages::Expected::RightBracketAfterListElements,
+            )?;
+
+            return Ok(expr::Expr::List(list_elements));
+        }
+        if self.matches(scanner::TokenType::LeftBrace) {
+            let brace_tok = self.previous().clone();
+            let mut entries = Vec::new();
+
+            if !self.check(scanner::TokenType::RightBrace) {
+                loop {
+                    let key = self.expression()?;
+                    self.consume(scanner::TokenType::Colon, messages::Expected::ColonAfterDictKey)?;
+                    let value = self.expression()?;
+                    entries.push((key, value));
+                    if !self.matches(scanner::TokenType::Comma) {
+                        break;
+                    }
+                }
+            }
+
+            self.consume(
+                scanner::TokenType::RightBrace,
+                messages::Expected::RightBraceAfterDictEntries,
+            )?;
+
+            return Ok(expr::Expr::Dict {
+                entries,
+                source_location: expr::SourceLocation {
+                    line: brace_tok.line,
+                    col: brace_tok.col,
+                },
+            });
+        }
+
+        let next = self.peek();
+        // the `}` that resumes an interpolated string, e.g. in `"${}"` or `"${a +}"`
+        if matches!(
+            next.ty,
+            scanner::TokenType::InterpolationMiddle | scanner::TokenType::InterpolationEnd
+        ) {
+            let col = if next.lexeme.contains(&'\n') {
+                next.col
+            } else {
+                next.col - (next.lexeme.len() as i64 - 1)
+            };
+            return Err(Error::ExpectedExpression {
+                token_type: scanner::TokenType::RightBrace,
+                line: next.line,
+                col,
+            });
+        }
 
         Err(Error::ExpectedExpression {
-            token_type: self.peek().ty,
-            line: self.peek().line,
-            col: self.peek().col,
+            token_type: next.ty,
+            line: next.line,
+            col: next.col,
         })
     }
 
     fn consume(
         &mut self,
         tok: scanner::TokenType,
-        on_err_str: &str,
+        expected: messages::Expected,
     ) -> Result<&scanner::Token, Error> {
         if self.check(tok) {
             return Ok(self.advance());
         }
         Err(Error::TokenMismatch {
-            expected: tok,
+            expected,
             found: self.peek().clone(),
-            maybe_on_err_string: Some(on_err_str.into()),
         })
     }
 
@@ -882,6 +1276,16 @@ impl Parser {
                 line: tok.line,
                 col: tok.col,
             }),
+            scanner::TokenType::Percent => Ok(expr::BinaryOp {
+                ty: expr::BinaryOpTy::Percent,
+                line: tok.line,
+                col: tok.col,
+            }),
+            scanner::TokenType::StarStar => Ok(expr::BinaryOp {
+                ty: expr::BinaryOpTy::StarStar,
+                line: tok.line,
+                col: tok.col,
+            }),
             _ => Err(Error::InvalidTokenInBinaryOp {
                 token_type: tok.ty,
                 line: tok.line,
@@ -915,6 +1319,14 @@ impl Parser {
         self.peek().ty == ty
     }
 
+    fn check_next(&self, ty: scanner::TokenType) -> bool {
+        if self.is_at_end() {
+            return false;
+        }
+
+        self.tokens[self.current + 1].ty == ty
+    }
+
     fn advance(&mut self) -> &scanner::Token {
         if !self.is_at_end() {
             self.current += 1
@@ -935,3 +1347,37 @@ impl Parser {
         &self.tokens[self.current - 1]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn error_before_closing_brace_is_reported_once() {
+        let tokens = scanner::scan_tokens_with(String::from("{ छाप }"), &[]).unwrap();
+        let errs = parse(tokens).unwrap_err();
+        assert_eq!(errs.len(), 1);
+        assert!(matches!(
+            errs[0],
+            Error::ExpectedExpression {
+                token_type: scanner::TokenType::RightBrace,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn string_after_interpolation_is_not_a_literal() {
+        for source in [r#"छाप "${"a" +} b" "c";"#, r#"छाप "${१ +}";"#] {
+            let tokens = scanner::scan_tokens_with(String::from(source), &[]).unwrap();
+            let errs = parse(tokens).unwrap_err();
+            assert!(matches!(
+                errs[0],
+                Error::ExpectedExpression {
+                    token_type: scanner::TokenType::RightBrace,
+                    ..
+                }
+            ));
+        }
+    }
+}
@@ -0,0 +1,207 @@
+use rustyline::error::ReadlineError;
+use rustyline::DefaultEditor;
+
+use std::sync::atomic::Ordering;
+
+use crate::dialect;
+use crate::diagnostics;
+use crate::expr;
+use crate::input;
+use crate::interpreter;
+use crate::messages;
+use crate::parser;
+use crate::resolver;
+use crate::scanner;
+
+const PROMPT: &str = "> ";
+const CONTINUATION_PROMPT: &str = ". ";
+
+pub fn run(dialect: Option<dialect::Dialect>) {
+    let mut editor = match DefaultEditor::new() {
+        Ok(editor) => editor,
+        Err(err) => {
+            eprintln!("Could not start the REPL: {}", err);
+            std::process::exit(-1);
+        }
+    };
+
+    let mut interpreter: interpreter::Interpreter = Default::default();
+    interpreter.dialect = dialect;
+
+    let interrupted = interpreter.interrupted.clone();
+    if let Err(err) = ctrlc::set_handler(move || interrupted.store(true, Ordering::Release)) {
+        eprintln!("Could not install Ctrl-C handler: {}", err);
+    }
+
+    // lines seen so far, so that symbols (and thus resolved locals) from different
+    // entries never share a line number.
+    let mut line_offset = 0;
+    let mut buffer = String::new();
+
+    loop {
+        let prompt = if buffer.is_empty() {
+            PROMPT
+        } else {
+            CONTINUATION_PROMPT
+        };
+
+        match editor.readline(prompt) {
+            Ok(line) => {
+                buffer.push_str(&line);
+                buffer.push('\n');
+
+                if !is_complete(&buffer) {
+                    continue;
+                }
+
+                let _ = editor.add_history_entry(buffer.trim_end());
+                let source = std::mem::take(&mut buffer);
+                let num_lines = source.lines().count();
+                eval(&mut interpreter, &source, line_offset);
+                line_offset += num_lines;
+            }
+            Err(ReadlineError::Interrupted) => {
+                buffer.clear();
+            }
+            Err(ReadlineError::Eof) => {
+                // report whatever was left unbalanced rather than silently dropping it
+                eval(&mut interpreter, &buffer, line_offset);
+                break;
+            }
+            Err(err) => {
+                eprintln!("{}", err);
+                break;
+            }
+        }
+    }
+}
+
+fn eval(interpreter: &mut interpreter::Interpreter, source: &str, line_offset: usize) {
+    let mut source = source.trim_end().to_string();
+    if source.is_empty() {
+        return;
+    }
+    // allow bare expressions like `१ + २` without a trailing semicolon
+    if !source.ends_with(';') && !source.ends_with('}') {
+        source.push(';');
+    }
+    let input = input::Input {
+        source: input::Source::Repl,
+        content: source,
+    };
+    let report = |kind: &str, (message, span): (String, Option<diagnostics::Span>)| {
+        eprint!(
+            "{}",
+            diagnostics::render(&input, line_offset + 1, kind, &message, span)
+        )
+    };
+
+    let extra_keywords = dialect::extra_keywords(interpreter.dialect.as_ref());
+    let mut tokens = match scanner::scan_tokens_with(input.content.clone(), extra_keywords) {
+        Ok(tokens) => tokens,
+        Err(mut err) => {
+            err.line += line_offset;
+            report(&messages::Message::ScannerErrorLabel.to_string(), diagnostics::scanner_error(&err));
+            return;
+        }
+    };
+    for token in tokens.iter_mut() {
+        token.line += line_offset;
+    }
+
+    let stmts = match parser::parse(tokens) {
+        Ok(stmts) => stmts,
+        Err(errs) => {
+            for err in errs.iter() {
+                report(&messages::Message::SyntaxErrorLabel.to_string(), diagnostics::parser_error(err));
+            }
+            return;
+        }
+    };
+
+    match resolver::resolve(&stmts) {
+        Ok(locals) => interpreter.resolve(locals),
+        Err(err) => {
+            report(&messages::Message::ResolveErrorLabel.to_string(), diagnostics::resolver_error(&err));
+            return;
+        }
+    }
+
+    let res = match stmts.as_slice() {
+        [expr::Stmt::Expr(e)] => interpreter
+            .interpret_expression(e)
+            .map(|val| println!("{}", interpreter.format_val(&val))),
+        _ => interpreter.interpret(&stmts),
+    };
+
+    if let Err(err) = res {
+        let kind = messages::Message::RuntimeErrorLabel.to_string();
+        let (message, span) = diagnostics::runtime_error(&err);
+        match interpreter.error_input() {
+            // modules number their lines from 1, not from the start of the session
+            Some(module_input) => eprint!(
+                "{}",
+                diagnostics::render(module_input, 1, &kind, &message, span)
+            ),
+            None => report(&kind, (message, span)),
+        }
+        eprintln!("\n{}", interpreter.format_backtrace(&err));
+        interpreter.backtrace.truncate(1);
+    }
+}
+
+/// Whether `source` has balanced braces, brackets and parens outside of strings and
+/// comments, i.e. whether the REPL should stop reading continuation lines.
+fn is_complete(source: &str) -> bool {
+    let mut depth: i64 = 0;
+    let mut in_string = false;
+    // how many `/*` comments we are inside of
+    let mut comment_depth = 0;
+    let mut chars = source.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if comment_depth > 0 {
+            match c {
+                '/' if chars.peek() == Some(&'*') => {
+                    chars.next();
+                    comment_depth += 1;
+                }
+                '*' if chars.peek() == Some(&'/') => {
+                    chars.next();
+                    comment_depth -= 1;
+                }
+                _ => {}
+            }
+            continue;
+        }
+        if in_string {
+            match c {
+                '"' => in_string = false,
+                '\\' => {
+                    chars.next();
+                }
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '/' if chars.peek() == Some(&'/') => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                comment_depth = 1;
+            }
+            '{' | '(' | '[' => depth += 1,
+            '}' | ')' | ']' => depth -= 1,
+            _ => {}
+        }
+    }
+
+    !in_string && comment_depth == 0 && depth <= 0
+}
@@ -0,0 +1,350 @@
+use crate::expr;
+use crate::messages;
+
+use std::collections::HashMap;
+use std::fmt;
+
+pub enum Error {
+    ReadLocalInOwnInitializer {
+        name: String,
+        line: usize,
+        col: i64,
+    },
+    ReturnNotInFun {
+        line: usize,
+        col: i64,
+    },
+    ThisOutsideClass {
+        line: usize,
+        col: i64,
+    },
+}
+
+impl fmt::Debug for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let message = match self {
+            Error::ReadLocalInOwnInitializer { name, .. } => {
+                messages::Message::ReadLocalInOwnInitializer(name)
+            }
+            Error::ReturnNotInFun { .. } => messages::Message::ReturnNotInFun,
+            Error::ThisOutsideClass { .. } => messages::Message::ThisOutsideClass,
+        };
+        write!(f, "{}", message)
+    }
+}
+
+#[derive(Copy, Clone, PartialEq)]
+enum FunctionKind {
+    None,
+    Function,
+}
+
+#[derive(Copy, Clone, PartialEq)]
+enum ClassKind {
+    None,
+    Class,
+}
+
+/// Maps every local variable reference (keyed by its symbol, which includes its source
+/// location) to the number of scopes between the reference and its declaration.
+/// References missing from the map are globals.
+pub type Locals = HashMap<expr::Symbol, usize>;
+
+pub fn resolve(stmts: &[expr::Stmt]) -> Result<Locals, Error> {
+    let mut resolver = Resolver {
+        scopes: Vec::new(),
+        locals: HashMap::new(),
+        current_function: FunctionKind::None,
+        current_class: ClassKind::None,
+    };
+
+    resolver.resolve_stmts(stmts)?;
+
+    Ok(resolver.locals)
+}
+
+pub fn this_symbol(line: usize, col: i64) -> expr::Symbol {
+    expr::Symbol {
+        name: String::from("this"),
+        line,
+        col,
+    }
+}
+
+struct Resolver {
+    // true once the variable's initializer has been resolved
+    scopes: Vec<HashMap<String, bool>>,
+    locals: Locals,
+    current_function: FunctionKind,
+    current_class: ClassKind,
+}
+
+impl Resolver {
+    fn resolve_stmts(&mut self, stmts: &[expr::Stmt]) -> Result<(), Error> {
+        for stmt in stmts {
+            self.resolve_stmt(stmt)?;
+        }
+        Ok(())
+    }
+
+    fn resolve_stmt(&mut self, stmt: &expr::Stmt) -> Result<(), Error> {
+        match stmt {
+            expr::Stmt::Expr(e) => self.resolve_expr(e),
+            expr::Stmt::FunDecl(expr::FunDecl {
+                name, params, body, ..
+            }) => {
+                self.declare(name);
+                self.define(name);
+                self.resolve_function(params, body, FunctionKind::Function)
+            }
+            expr::Stmt::ClassDecl(expr::ClassDecl {
+                name,
+                superclass,
+                methods,
+                ..
+            }) => {
+                let enclosing_class = self.current_class;
+                self.current_class = ClassKind::Class;
+
+                self.declare(name);
+                self.define(name);
+
+                if let Some(superclass) = superclass {
+                    self.resolve_local(superclass);
+                }
+
+                for method in methods {
+                    self.begin_scope();
+                    self.define(&this_symbol(method.name.line, method.name.col));
+                    let res = self.resolve_function(&method.params, &method.body, FunctionKind::Function);
+                    self.end_scope();
+                    if let Err(err) = res {
+                        self.current_class = enclosing_class;
+                        return Err(err);
+                    }
+                }
+
+                self.current_class = enclosing_class;
+                Ok(())
+            }
+            expr::Stmt::If(cond, then_branch, maybe_else_branch) => {
+                self.resolve_expr(cond)?;
+                self.resolve_stmt(then_branch)?;
+                if let Some(else_branch) = maybe_else_branch {
+                    self.resolve_stmt(else_branch)?;
+                }
+                Ok(())
+            }
+            expr::Stmt::Print(e) => self.resolve_expr(e),
+            expr::Stmt::VarDecl(sym, maybe_initializer) => {
+                self.declare(sym);
+                if let Some(initializer) = maybe_initializer {
+                    self.resolve_expr(initializer)?;
+                }
+                self.define(sym);
+                Ok(())
+            }
+            expr::Stmt::Block(stmts) => {
+                self.begin_scope();
+                let res = self.resolve_stmts(stmts);
+                self.end_scope();
+                res
+            }
+            expr::Stmt::Return(loc, maybe_retval) => {
+                if self.current_function == FunctionKind::None {
+                    return Err(Error::ReturnNotInFun {
+                        line: loc.line,
+                        col: loc.col,
+                    });
+                }
+                match maybe_retval {
+                    Some(retval) => self.resolve_expr(retval),
+                    None => Ok(()),
+                }
+            }
+            expr::Stmt::While(cond, body, maybe_increment) => {
+                self.resolve_expr(cond)?;
+                self.resolve_stmt(body)?;
+                match maybe_increment {
+                    Some(increment) => self.resolve_expr(increment),
+                    None => Ok(()),
+                }
+            }
+            expr::Stmt::Break | expr::Stmt::Continue => Ok(()),
+            expr::Stmt::Throw(_, value) => self.resolve_expr(value),
+            expr::Stmt::Import { name, .. } => {
+                self.declare(name);
+                self.define(name);
+                Ok(())
+            }
+            expr::Stmt::Try {
+                body,
+                catch,
+                finally,
+            } => {
+                self.begin_scope();
+                let res = self.resolve_stmts(body);
+                self.end_scope();
+                res?;
+
+                if let Some(expr::Catch { name, body }) = catch {
+                    self.begin_scope();
+                    self.declare(name);
+                    self.define(name);
+                    let res = self.resolve_stmts(body);
+                    self.end_scope();
+                    res?;
+                }
+
+                if let Some(finally) = finally {
+                    self.begin_scope();
+                    let res = self.resolve_stmts(finally);
+                    self.end_scope();
+                    res?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn resolve_function(
+        &mut self,
+        params: &[expr::Symbol],
+        body: &[expr::Stmt],
+        kind: FunctionKind,
+    ) -> Result<(), Error> {
+        let enclosing_function = self.current_function;
+        self.current_function = kind;
+
+        self.begin_scope();
+        for param in params {
+            self.declare(param);
+            self.define(param);
+        }
+        let res = self.resolve_stmts(body);
+        self.end_scope();
+
+        self.current_function = enclosing_function;
+        res
+    }
+
+    fn resolve_expr(&mut self, e: &expr::Expr) -> Result<(), Error> {
+        match e {
+            expr::Expr::Literal(_) => Ok(()),
+            expr::Expr::This(loc) => {
+                if self.current_class == ClassKind::None {
+                    return Err(Error::ThisOutsideClass {
+                        line: loc.line,
+                        col: loc.col,
+                    });
+                }
+                self.resolve_local(&this_symbol(loc.line, loc.col));
+                Ok(())
+            }
+            expr::Expr::Unary(_, e) => self.resolve_expr(e),
+            expr::Expr::Binary(lhs, _, rhs) => {
+                self.resolve_expr(lhs)?;
+                self.resolve_expr(rhs)
+            }
+            expr::Expr::Call(callee, _, args) => {
+                self.resolve_expr(callee)?;
+                for arg in args {
+                    self.resolve_expr(arg)?;
+                }
+                Ok(())
+            }
+            expr::Expr::Get(lhs, _) => self.resolve_expr(lhs),
+            expr::Expr::Grouping(e) => self.resolve_expr(e),
+            expr::Expr::Variable(sym) => {
+                if let Some(scope) = self.scopes.last() {
+                    if scope.get(&sym.name) == Some(&false) {
+                        return Err(Error::ReadLocalInOwnInitializer {
+                            name: sym.name.clone(),
+                            line: sym.line,
+                            col: sym.col,
+                        });
+                    }
+                }
+                self.resolve_local(sym);
+                Ok(())
+            }
+            expr::Expr::Assign(sym, val) => {
+                self.resolve_expr(val)?;
+                self.resolve_local(sym);
+                Ok(())
+            }
+            expr::Expr::Logical(lhs, _, rhs) => {
+                self.resolve_expr(lhs)?;
+                self.resolve_expr(rhs)
+            }
+            expr::Expr::Set(lhs, _, rhs) => {
+                self.resolve_expr(rhs)?;
+                self.resolve_expr(lhs)
+            }
+            expr::Expr::Super(loc, _) => {
+                // super methods are bound to the enclosing method's यो
+                self.resolve_local(&this_symbol(loc.line, loc.col));
+                Ok(())
+            }
+            expr::Expr::Lambda(expr::LambdaDecl { params, body, .. }) => {
+                self.resolve_function(params, body, FunctionKind::Function)
+            }
+            expr::Expr::List(elements) | expr::Expr::Interpolation(elements) => {
+                for element in elements {
+                    self.resolve_expr(element)?;
+                }
+                Ok(())
+            }
+            expr::Expr::Dict { entries, .. } => {
+                for (key, value) in entries {
+                    self.resolve_expr(key)?;
+                    self.resolve_expr(value)?;
+                }
+                Ok(())
+            }
+            expr::Expr::Subscript { value, slice, .. } => {
+                self.resolve_expr(value)?;
+                self.resolve_expr(slice)
+            }
+            expr::Expr::SetItem { lhs, slice, rhs, .. } => {
+                self.resolve_expr(lhs)?;
+                self.resolve_expr(slice)?;
+                self.resolve_expr(rhs)
+            }
+            expr::Expr::CompoundAssign { target, value, .. } => {
+                self.resolve_expr(value)?;
+                self.resolve_expr(target)
+            }
+            expr::Expr::Increment { target, .. } => self.resolve_expr(target),
+        }
+    }
+
+    fn resolve_local(&mut self, sym: &expr::Symbol) {
+        for (depth, scope) in self.scopes.iter().rev().enumerate() {
+            if scope.contains_key(&sym.name) {
+                self.locals.insert(sym.clone(), depth);
+                return;
+            }
+        }
+    }
+
+    fn begin_scope(&mut self) {
+        self.scopes.push(HashMap::new());
+    }
+
+    fn end_scope(&mut self) {
+        self.scopes.pop();
+    }
+
+    fn declare(&mut self, sym: &expr::Symbol) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(sym.name.clone(), false);
+        }
+    }
+
+    fn define(&mut self, sym: &expr::Symbol) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(sym.name.clone(), true);
+        }
+    }
+}
@@ -1,3 +1,5 @@
+use crate::messages;
+
 use std::collections::HashMap;
 use std::fmt;
 
@@ -10,13 +12,14 @@ pub enum TokenType {
     RightBrace,
     LeftBracket,
     RightBracket,
+    Colon,
     Comma,
     Dot,
     Minus,
+    Percent,
     Plus,
     Semicolon,
     Slash,
-    Star,
 
     // One or two character tokens.
     Bang,
@@ -25,29 +28,50 @@ pub enum TokenType {
     EqualEqual,
     Greater,
     GreaterEqual,
+    Star,
+    StarStar,
+    StarEqual,
+    PlusEqual,
+    MinusEqual,
+    PlusPlus,
+    MinusMinus,
+    SlashEqual,
     Less,
     LessEqual,
 
     // Literals.
     Identifier,
     String,
+    /// The start of an interpolated string, up to its first `${`: `"a ${`.
+    Interpolation,
+    /// The part of an interpolated string between two embedded expressions: `} b ${`.
+    InterpolationMiddle,
+    /// The end of an interpolated string, after its last embedded expression: `} b"`.
+    InterpolationEnd,
     Number,
 
     // Keywords.
     And,
+    Break,
+    Catch,
     Class,
+    Continue,
     Else,
     False,
+    Finally,
     Fun,
     For,
     If,
+    Import,
     Nil,
     Or,
     Print,
     Return,
     Super,
     This,
+    Throw,
     True,
+    Try,
     Var,
     While,
 
@@ -61,6 +85,32 @@ pub enum Literal {
     Number(f64),
 }
 
+/// Source text between tokens, which (but for doc comments) only `scan_lossless` keeps.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Trivia {
+    /// Spaces, tabs and carriage returns.
+    Whitespace(String),
+    Newline,
+    /// A `//` comment, without the newline that ends it.
+    LineComment(String),
+    /// A `/* */` comment, with the comments nested in it.
+    BlockComment(String),
+    /// A `///` comment, which documents the function or class after it.
+    DocComment(String),
+}
+
+impl Trivia {
+    pub fn text(&self) -> &str {
+        match self {
+            Trivia::Whitespace(text)
+            | Trivia::LineComment(text)
+            | Trivia::BlockComment(text)
+            | Trivia::DocComment(text) => text,
+            Trivia::Newline => "\n",
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct Token {
     pub ty: TokenType,
@@ -68,6 +118,32 @@ pub struct Token {
     pub literal: Option<Literal>,
     pub line: usize,
     pub col: i64,
+    /// Only kept by `scan_lossless`, except for doc comments before the token.
+    pub trivia: Option<Box<TokenTrivia>>,
+}
+
+impl Token {
+    pub fn leading_trivia(&self) -> &[Trivia] {
+        match &self.trivia {
+            Some(trivia) => &trivia.leading,
+            None => &[],
+        }
+    }
+
+    pub fn trailing_trivia(&self) -> &[Trivia] {
+        match &self.trivia {
+            Some(trivia) => &trivia.trailing,
+            None => &[],
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TokenTrivia {
+    /// The trivia since the end of the previous token's line (or the start of the source).
+    pub leading: Vec<Trivia>,
+    /// The trivia after the token on its own line; the newline is the next token's.
+    pub trailing: Vec<Trivia>,
 }
 
 impl fmt::Debug for Token {
@@ -84,8 +160,85 @@ impl fmt::Debug for Token {
     }
 }
 
-pub fn scan_tokens(input: String) -> Result<Vec<Token>, Error> {
-    let mut scanner: Scanner = Default::default();
+/// Every keyword, spelled in Devanagari and in roman letters.
+pub const KEYWORDS: [(TokenType, &str, &str); 23] = [
+    (TokenType::And, "अनि", "ani"),
+    (TokenType::Break, "टुट", "tut"),
+    (TokenType::Class, "वर्ग", "barga"),
+    (TokenType::Continue, "अर्को", "arko"),
+    (TokenType::Else, "अरु", "natra"),
+    (TokenType::False, "गलत", "galat"),
+    (TokenType::For, "भुमरी", "bhumari"),
+    (TokenType::Fun, "काम", "kaam"),
+    (TokenType::If, "यदि", "yadi"),
+    (TokenType::Nil, "आलु", "aalu"),
+    (TokenType::Or, "वा", "wa"),
+    (TokenType::Print, "छाप", "chap"),
+    (TokenType::Return, "रिटन", "return"),
+    (TokenType::Super, "सुपर", "super"),
+    (TokenType::This, "यो", "yo"),
+    (TokenType::True, "सहि", "sahi"),
+    (TokenType::Var, "भार", "ghosana"),
+    (TokenType::While, "जबसम्म", "jabasamma"),
+    (TokenType::Import, "आयात", "aayat"),
+    (TokenType::Throw, "फाल", "phaal"),
+    (TokenType::Try, "प्रयास", "prayas"),
+    (TokenType::Catch, "समात", "samaat"),
+    (TokenType::Finally, "अन्त्यमा", "antyama"),
+];
+
+/// Other Devanagari spellings the scanner accepts.
+pub const KEYWORD_ALIASES: [(&str, TokenType); 2] = [("नत्र", TokenType::Else), ("फर", TokenType::For)];
+
+pub const DEVANAGARI_DIGITS: [char; 10] = ['०', '१', '२', '३', '४', '५', '६', '७', '८', '९'];
+
+/// Every built-in keyword spelling and the keyword it stands for.
+pub fn keywords() -> HashMap<String, TokenType> {
+    KEYWORDS
+        .iter()
+        .flat_map(|(ty, devanagari, roman)| vec![(*devanagari, *ty), (*roman, *ty)])
+        .chain(KEYWORD_ALIASES.iter().copied())
+        .map(|(k, v)| (String::from(k), v))
+        .collect()
+}
+
+/// Whether the scanner reads `s` as a single identifier (or keyword).
+pub fn is_identifier(s: &str) -> bool {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(c) => Scanner::is_alpha(c) && chars.all(Scanner::is_alphanumeric),
+        None => false,
+    }
+}
+
+/// Scans `input`, accepting the given keyword spellings (from a dialect) on top of the
+/// built-in ones.
+pub fn scan_tokens_with(
+    input: String,
+    extra_keywords: &[(String, TokenType)],
+) -> Result<Vec<Token>, Error> {
+    scan(input, extra_keywords, false)
+}
+
+/// Like `scan_tokens_with`, but the tokens also carry the whitespace and comments around
+/// them, so `source_text` gives back `input` exactly.
+pub fn scan_lossless(
+    input: String,
+    extra_keywords: &[(String, TokenType)],
+) -> Result<Vec<Token>, Error> {
+    scan(input, extra_keywords, true)
+}
+
+fn scan(
+    input: String,
+    extra_keywords: &[(String, TokenType)],
+    lossless: bool,
+) -> Result<Vec<Token>, Error> {
+    let mut scanner = Scanner {
+        lossless,
+        ..Default::default()
+    };
+    scanner.keywords.extend(extra_keywords.iter().cloned());
 
     scanner.scan_tokens(input);
 
@@ -95,6 +248,21 @@ pub fn scan_tokens(input: String) -> Result<Vec<Token>, Error> {
     }
 }
 
+/// The source text of `tokens`, trivia included.
+pub fn source_text(tokens: &[Token]) -> String {
+    let mut res = String::new();
+    for tok in tokens.iter() {
+        for trivia in tok.leading_trivia() {
+            res.push_str(trivia.text());
+        }
+        res.extend(tok.lexeme.iter());
+        for trivia in tok.trailing_trivia() {
+            res.push_str(trivia.text());
+        }
+    }
+    res
+}
+
 #[derive(Debug)]
 pub struct Error {
     pub what: String,
@@ -111,6 +279,14 @@ struct Scanner {
     line: usize,
     col: i64,
     keywords: HashMap<String, TokenType>,
+    /// Whether to keep trivia, and the trivia not yet attached to a token.
+    lossless: bool,
+    trivia: Vec<Trivia>,
+    /// Whether a token was scanned since the last newline.
+    on_token_line: bool,
+    /// For every `${` we are inside of: how many unclosed `{` it contains, and the line
+    /// and column where its string started.
+    interpolations: Vec<(usize, usize, i64)>,
 }
 
 impl Default for Scanner {
@@ -123,46 +299,11 @@ impl Default for Scanner {
             current: 0,
             line: 1,
             col: -1,
-            keywords: vec![
-                ("अनि", TokenType::And),
-                ("वर्ग", TokenType::Class),
-                ("अरु", TokenType::Else),
-                ("नत्र", TokenType::Else),
-                ("गलत", TokenType::False),
-                ("भुमरी", TokenType::For),
-                ("फर", TokenType::For),
-                ("काम", TokenType::Fun),
-                ("यदि", TokenType::If),
-                ("आलु", TokenType::Nil),
-                ("वा", TokenType::Or),
-                ("छाप", TokenType::Print),
-                ("रिटन", TokenType::Return),
-                ("सुपर", TokenType::Super),
-                ("यो", TokenType::This),
-                ("सहि", TokenType::True),
-                ("भार", TokenType::Var),
-                ("जबसम्म", TokenType::While),
-
-                ("ani", TokenType::And),
-                ("barga", TokenType::Class),
-                ("natra", TokenType::Else),
-                ("galat", TokenType::False),
-                ("bhumari", TokenType::For),
-                ("kaam", TokenType::Fun),
-                ("yadi", TokenType::If),
-                ("aalu", TokenType::Nil),
-                ("wa", TokenType::Or),
-                ("chap", TokenType::Print),
-                ("return", TokenType::Return),
-                ("super", TokenType::Super),
-                ("yo", TokenType::This),
-                ("sahi", TokenType::True),
-                ("ghosana", TokenType::Var),
-                ("jabasamma", TokenType::While),
-            ]
-            .into_iter()
-            .map(|(k, v)| (String::from(k), v))
-            .collect(),
+            interpolations: Vec::new(),
+            keywords: keywords(),
+            lossless: false,
+            trivia: Vec::new(),
+            on_token_line: false,
         }
     }
 }
@@ -176,15 +317,27 @@ impl Scanner {
             self.scan_token();
         }
 
+        if let (None, Some((_, line, col))) = (&self.err, self.interpolations.first()) {
+            self.err = Some(Error {
+                what: messages::Message::UnterminatedString.to_string(),
+                line: *line,
+                col: *col,
+            });
+        }
+
         match self.err {
             Some(_) => {}
-            None => self.tokens.push(Token {
-                ty: TokenType::Eof,
-                lexeme: Vec::new(),
-                literal: None,
-                line: self.line,
-                col: self.col,
-            }),
+            None => {
+                let trivia = self.take_trivia();
+                self.tokens.push(Token {
+                    ty: TokenType::Eof,
+                    lexeme: Vec::new(),
+                    literal: None,
+                    line: self.line,
+                    col: self.col,
+                    trivia,
+                })
+            }
         }
     }
 
@@ -201,16 +354,62 @@ impl Scanner {
         match c {
             '(' => self.add_token(TokenType::LeftParen),
             ')' => self.add_token(TokenType::RightParen),
-            '{' => self.add_token(TokenType::LeftBrace),
-            '}' => self.add_token(TokenType::RightBrace),
+            '{' => {
+                if let Some((depth, _, _)) = self.interpolations.last_mut() {
+                    *depth += 1;
+                }
+                self.add_token(TokenType::LeftBrace)
+            }
+            '}' => match self.interpolations.last_mut() {
+                // the end of an embedded expression, back to the string
+                Some((0, line, col)) => {
+                    let (line, col) = (*line, *col);
+                    self.interpolations.pop();
+                    self.string_from(line, col, true)
+                }
+                Some((depth, _, _)) => {
+                    *depth -= 1;
+                    self.add_token(TokenType::RightBrace)
+                }
+                None => self.add_token(TokenType::RightBrace),
+            },
             '[' => self.add_token(TokenType::LeftBracket),
             ']' => self.add_token(TokenType::RightBracket),
+            ':' => self.add_token(TokenType::Colon),
             ',' => self.add_token(TokenType::Comma),
             '.' => self.add_token(TokenType::Dot),
-            '-' => self.add_token(TokenType::Minus),
-            '+' => self.add_token(TokenType::Plus),
+            '-' => {
+                let ty = if self.matches('-') {
+                    TokenType::MinusMinus
+                } else if self.matches('=') {
+                    TokenType::MinusEqual
+                } else {
+                    TokenType::Minus
+                };
+                self.add_token(ty)
+            }
+            '+' => {
+                let ty = if self.matches('+') {
+                    TokenType::PlusPlus
+                } else if self.matches('=') {
+                    TokenType::PlusEqual
+                } else {
+                    TokenType::Plus
+                };
+                self.add_token(ty)
+            }
             ';' => self.add_token(TokenType::Semicolon),
-            '*' => self.add_token(TokenType::Star),
+            '%' => self.add_token(TokenType::Percent),
+            '*' => {
+                let ty = if self.matches('*') {
+                    TokenType::StarStar
+                } else if self.matches('=') {
+                    TokenType::StarEqual
+                } else {
+                    TokenType::Star
+                };
+                self.add_token(ty)
+            }
             '!' => {
                 let matches_eq = self.matches('=');
                 self.add_token(if matches_eq {
@@ -245,9 +444,20 @@ impl Scanner {
             }
             '/' => {
                 if self.matches('/') {
+                    let doc = self.peek() == '/' && self.peek_next() != '/';
                     while self.peek() != '\n' && !self.is_at_end() {
                         self.advance();
                     }
+                    let text = self.source[self.start..self.current].iter().collect();
+                    if doc {
+                        self.add_trivia(Trivia::DocComment(text));
+                    } else {
+                        self.add_trivia(Trivia::LineComment(text));
+                    }
+                } else if self.matches('*') {
+                    self.block_comment()
+                } else if self.matches('=') {
+                    self.add_token(TokenType::SlashEqual)
                 } else {
                     self.add_token(TokenType::Slash)
                 }
@@ -257,8 +467,8 @@ impl Scanner {
                     while self.peek() != 'ी' {
                         self.advance();
                     }
-                        self.add_token(TokenType::Semicolon); 
                         self.advance();
+                        self.add_token(TokenType::Semicolon); 
                     }else{
                         self.identifier()
                     }
@@ -269,8 +479,8 @@ impl Scanner {
                  self.advance();
                  if self.matches('j') == true {
                      if self.peek() == 'i'{ 
-                        self.add_token(TokenType::Semicolon); 
                         self.advance();
+                        self.add_token(TokenType::Semicolon); 
                         didfind = true;
                      }
                  }
@@ -280,10 +490,11 @@ impl Scanner {
              }
             }     
 
-            ' ' | '\r' | '\t' => {}
+            ' ' | '\r' | '\t' => self.add_trivia(Trivia::Whitespace(c.to_string())),
             '\n' => {
                 self.line += 1;
-                self.col = 0
+                self.col = -1;
+                self.add_trivia(Trivia::Newline)
             }
             '"' => self.string(),
             _ => {
@@ -292,10 +503,8 @@ impl Scanner {
                 } else if Scanner::is_alpha(c) {
                     self.identifier()
                 } else {
-                    println!("{} is ",c);
-                    
                     self.err = Some(Error {
-                        what: format!("scanner can't handle {}", c),
+                        what: messages::Message::UnexpectedCharacter(c).to_string(),
                         line: self.line,
                         col: self.col,
                     })
@@ -309,7 +518,15 @@ impl Scanner {
     }
 
     fn is_decimal_digit(c: char) -> bool {
-        c.is_ascii_digit()
+        c.is_ascii_digit() || DEVANAGARI_DIGITS.contains(&c)
+    }
+
+    /// The ASCII digit for a Devanagari one, anything else unchanged.
+    fn ascii_digit(c: char) -> char {
+        match DEVANAGARI_DIGITS.iter().position(|d| *d == c) {
+            Some(pos) => char::from_digit(pos as u32, 10).unwrap(),
+            None => c,
+        }
     }
 
     fn is_alphanumeric(c: char) -> bool {
@@ -351,40 +568,168 @@ impl Scanner {
             self.advance();
         }
 
-        let val: f64 = self.source[self.start..self.current].iter().
-        collect::<String>().
-        parse::<f64>().
-        unwrap();
+        let val: f64 = self.source[self.start..self.current]
+            .iter()
+            .map(|c| Scanner::ascii_digit(*c))
+            .collect::<String>()
+            .parse::<f64>()
+            .unwrap();
 
         self.add_token_literal(TokenType::Number, Some(Literal::Number(val)))
     }
 
+    /// Scans past the `*/` that closes a `/*`, skipping nested `/* */` pairs.
+    fn block_comment(&mut self) {
+        let (start_line, start_col) = (self.line, self.col - 1);
+        let mut depth = 1;
+
+        while depth > 0 {
+            if self.is_at_end() {
+                self.err = Some(Error {
+                    what: messages::Message::UnterminatedComment.to_string(),
+                    line: start_line,
+                    col: start_col,
+                });
+                return;
+            }
+
+            // `matches` is true at the end of the input, so look before taking
+            match self.advance() {
+                '/' if !self.is_at_end() && self.peek() == '*' => {
+                    self.advance();
+                    depth += 1
+                }
+                '*' if !self.is_at_end() && self.peek() == '/' => {
+                    self.advance();
+                    depth -= 1
+                }
+                '\n' => {
+                    self.line += 1;
+                    self.col = -1
+                }
+                _ => {}
+            }
+        }
+
+        let text = self.source[self.start..self.current].iter().collect();
+        self.add_trivia(Trivia::BlockComment(text));
+    }
+
     fn string(&mut self) {
+        self.string_from(self.line, self.col, false)
+    }
+
+    /// Scans up to the closing quote, or up to the next `${`, which produces an
+    /// `Interpolation` token and continues the string at the matching `}`. `resumed`
+    /// is set when continuing after a `}`, whose pieces get their own token types.
+    fn string_from(&mut self, start_line: usize, start_col: i64, resumed: bool) {
+        let mut val = String::new();
+
         while self.peek() != '"' && !self.is_at_end() {
-            if self.peek() == '\n' {
-                self.line += 1
+            if self.peek() == '$' && self.peek_next() == '{' {
+                self.advance();
+                self.advance();
+                self.interpolations.push((0, start_line, start_col));
+                let ty = if resumed {
+                    TokenType::InterpolationMiddle
+                } else {
+                    TokenType::Interpolation
+                };
+                self.add_token_literal(ty, Some(Literal::Str(val)));
+                return;
+            }
+            match self.advance() {
+                '\n' => {
+                    self.line += 1;
+                    self.col = -1;
+                    val.push('\n');
+                }
+                '\\' => match self.escape() {
+                    Ok(Some(c)) => val.push(c),
+                    // a lone backslash at the very end; reported as unterminated below
+                    Ok(None) => {}
+                    Err(err) => {
+                        self.err = Some(err);
+                        return;
+                    }
+                },
+                c => val.push(c),
             }
-            self.advance();
         }
 
         if self.is_at_end() {
             self.err = Some(Error {
-                what: "Unterminated string".to_string(),
-                line: self.line,
-                col: self.col,
-            })
+                what: messages::Message::UnterminatedString.to_string(),
+                line: start_line,
+                col: start_col,
+            });
+            return;
         }
 
-        assert!(self.peek() == '"');
+        self.advance();
+
+        let ty = if resumed {
+            TokenType::InterpolationEnd
+        } else {
+            TokenType::String
+        };
+        self.add_token_literal(ty, Some(Literal::Str(val)))
+    }
+
+    /// The character for the escape sequence following a backslash, or `None` if the
+    /// input ends right after the backslash.
+    fn escape(&mut self) -> Result<Option<char>, Error> {
+        let (line, col) = (self.line, self.col);
+        if self.is_at_end() {
+            return Ok(None);
+        }
 
+        let c = match self.advance() {
+            'n' => '\n',
+            't' => '\t',
+            'r' => '\r',
+            '0' => '\0',
+            '"' => '"',
+            '\\' => '\\',
+            '$' => '$',
+            'u' => return self.unicode_escape(line, col).map(Some),
+            c => {
+                return Err(Error {
+                    what: messages::Message::UnknownEscape(c).to_string(),
+                    line,
+                    col,
+                })
+            }
+        };
+        Ok(Some(c))
+    }
+
+    /// `\u{0928}`: one to six hex digits naming a unicode scalar value.
+    fn unicode_escape(&mut self, line: usize, col: i64) -> Result<char, Error> {
+        let invalid = || Error {
+            what: messages::Message::InvalidUnicodeEscape.to_string(),
+            line,
+            col,
+        };
+
+        if self.peek() != '{' {
+            return Err(invalid());
+        }
         self.advance();
 
-        self.add_token_literal(
-            TokenType::String,
-            Some(Literal::Str(
-                self.source[self.start + 1..self.current - 1].iter().collect(),
-            )),
-        )
+        let mut digits = String::new();
+        while self.peek().is_ascii_hexdigit() {
+            digits.push(self.advance());
+        }
+        if self.peek() != '}' || digits.is_empty() || digits.len() > 6 {
+            return Err(invalid());
+        }
+        self.advance();
+
+        u32::from_str_radix(&digits, 16)
+            .ok()
+            .and_then(char::from_u32)
+            .ok_or_else(invalid)
     }
 
     fn peek_next(&self) -> char {
@@ -422,6 +767,8 @@ impl Scanner {
 
     fn add_token_literal(&mut self, token_type: TokenType, literal: Option<Literal>) {
         let text = self.source[self.start..self.current].to_vec();
+        let trivia = self.take_trivia();
+        self.on_token_line = true;
 
         self.tokens.push(Token {
             ty: token_type,
@@ -429,9 +776,45 @@ impl Scanner {
             literal,
             line: self.line,
             col: self.col,
+            trivia,
         })
     }
 
+    /// The trivia for a new token: everything not attached to the one before.
+    fn take_trivia(&mut self) -> Option<Box<TokenTrivia>> {
+        if !self.lossless && self.trivia.is_empty() {
+            return None;
+        }
+        Some(Box::new(TokenTrivia {
+            leading: std::mem::take(&mut self.trivia),
+            trailing: Vec::new(),
+        }))
+    }
+
+    /// Keeps trivia (in lossless mode): on the last token while still on its line, or
+    /// else for the next token.
+    fn add_trivia(&mut self, trivia: Trivia) {
+        let trailing = self.on_token_line && trivia != Trivia::Newline;
+        if trivia == Trivia::Newline {
+            self.on_token_line = false;
+        }
+        // the parser needs doc comments, so those before a token are always kept
+        let leading_doc = matches!(trivia, Trivia::DocComment(_)) && !trailing;
+        if !self.lossless && !leading_doc {
+            return;
+        }
+
+        let last = self.tokens.last_mut().and_then(|tok| tok.trivia.as_mut());
+        let list = match last {
+            Some(last) if trailing => &mut last.trailing,
+            _ => &mut self.trivia,
+        };
+        match (list.last_mut(), &trivia) {
+            (Some(Trivia::Whitespace(text)), Trivia::Whitespace(more)) => text.push_str(more),
+            _ => list.push(trivia),
+        }
+    }
+
     fn done(&self) -> bool {
         self.err.is_some() || self.is_at_end()
     }
@@ -440,3 +823,25 @@ impl Scanner {
         self.current >= self.source.len()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(source: &str) {
+        let tokens = scan_lossless(String::from(source), &[]).unwrap();
+        assert_eq!(source_text(&tokens), source);
+    }
+
+    #[test]
+    fn lossless_scan_reproduces_the_source() {
+        round_trip("भार क = १;\r\nछाप क;\r\n\r\n");
+        round_trip("/* a /* b */ c */ छाप १; /* /* */ */\n/** doc */\n");
+        round_trip("/// दुई सङ्ख्या जोड्छ।\nकाम जोड(a, b) {\n\tरिटन a + b;\n}\n");
+        round_trip("छाप \"a ${\"b ${१ + २}\"} c ${ {\"k\": [x]}[\"k\"] }\\n\\u{0928}\";\n");
+        round_trip("छाप \"दुई\nरेखा ${x}\n\";");
+        round_trip("chap 1 ; // end");
+        round_trip("  \n// only a comment");
+        round_trip("");
+    }
+}
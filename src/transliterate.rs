@@ -0,0 +1,346 @@
+//! Rewrites a program between Devanagari and romanized source.
+//!
+//! Only the text of keywords (and, if asked, identifiers and numbers) is replaced;
+//! everything the scanner skips or keeps verbatim, like comments, whitespace and string
+//! contents, is copied unchanged.
+
+use crate::dialect;
+use crate::interpreter;
+use crate::scanner;
+
+use std::collections::HashMap;
+use std::str::FromStr;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Script {
+    Devanagari,
+    Roman,
+}
+
+impl FromStr for Script {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Script, String> {
+        match s.to_lowercase().as_str() {
+            "devanagari" | "ne" | "देवनागरी" => Ok(Script::Devanagari),
+            "roman" | "en" | "रोमन" => Ok(Script::Roman),
+            _ => Err(format!(
+                "unknown script '{}' (expected 'devanagari' or 'roman') / अज्ञात लिपि '{}' ('devanagari' वा 'roman' हुनुपर्छ)",
+                s, s
+            )),
+        }
+    }
+}
+
+pub struct Options {
+    /// The script to convert to; by default the opposite of the program's first keyword.
+    pub to: Option<Script>,
+    pub dialect: Option<dialect::Dialect>,
+    pub identifiers: bool,
+    pub digits: bool,
+}
+
+#[derive(Debug)]
+pub enum Error {
+    Scan(scanner::Error),
+    KeywordClash {
+        name: String,
+        keyword: String,
+        line: usize,
+        col: i64,
+    },
+    /// `name` would be spelled `new_name`, which `other` is already called or becomes.
+    NameClash {
+        name: String,
+        other: String,
+        new_name: String,
+        line: usize,
+        col: i64,
+    },
+}
+
+pub fn transliterate(source: &str, options: &Options) -> Result<String, Error> {
+    let extra_keywords = dialect::extra_keywords(options.dialect.as_ref());
+    let mut tokens =
+        scanner::scan_lossless(source.to_string(), extra_keywords).map_err(Error::Scan)?;
+
+    let to = options.to.unwrap_or_else(|| {
+        match tokens
+            .iter()
+            .find(|tok| keyword_spellings(tok.ty).is_some())
+        {
+            Some(tok) if tok.lexeme.iter().all(char::is_ascii) => Script::Devanagari,
+            Some(_) => Script::Roman,
+            None => Script::Devanagari,
+        }
+    });
+    let builtins = interpreter::builtin_names();
+    let renamed = |name: &String| {
+        if options.identifiers && !builtins.contains(name) {
+            transliterate_name(name, to, options.digits)
+        } else {
+            None
+        }
+    };
+
+    // the final spelling of every identifier, and the name in the source it comes from;
+    // starting with those that keep their spelling
+    let mut spellings: HashMap<String, String> = tokens
+        .iter()
+        .filter(|tok| tok.ty == scanner::TokenType::Identifier)
+        .map(|tok| tok.lexeme.iter().collect::<String>())
+        .filter(|name| renamed(name).is_none())
+        .map(|name| (name.clone(), name))
+        .collect();
+
+    for tok in tokens.iter_mut() {
+        let name: String = tok.lexeme.iter().collect();
+        let replacement = match tok.ty {
+            scanner::TokenType::Identifier => {
+                match renamed(&name) {
+                    Some(new_name) => {
+                        let dialect_keyword = extra_keywords
+                            .iter()
+                            .find(|(spelling, _)| *spelling == new_name)
+                            .map(|(spelling, _)| spelling.as_str());
+                        if let Some(keyword) = keyword_named(&new_name).or(dialect_keyword) {
+                            return Err(Error::KeywordClash {
+                                name,
+                                keyword: String::from(keyword),
+                                line: tok.line,
+                                col: tok.col - (tok.lexeme.len() as i64 - 1),
+                            });
+                        }
+                        match spellings.get(&new_name) {
+                            Some(other) if *other != name => {
+                                return Err(Error::NameClash {
+                                    other: other.clone(),
+                                    name,
+                                    new_name,
+                                    line: tok.line,
+                                    col: tok.col - (tok.lexeme.len() as i64 - 1),
+                                });
+                            }
+                            Some(_) => {}
+                            None => {
+                                spellings.insert(new_name.clone(), name.clone());
+                            }
+                        }
+                        Some(new_name)
+                    }
+                    None => None,
+                }
+            }
+            scanner::TokenType::Number if options.digits => Some(convert_digits(&name, to)),
+            ty => keyword_spellings(ty).map(|(_, devanagari, roman)| match to {
+                Script::Devanagari => String::from(*devanagari),
+                Script::Roman => String::from(*roman),
+            }),
+        };
+
+        if let Some(replacement) = replacement {
+            tok.lexeme = replacement.chars().collect();
+        }
+    }
+
+    Ok(scanner::source_text(&tokens))
+}
+
+fn keyword_spellings(
+    ty: scanner::TokenType,
+) -> Option<&'static (scanner::TokenType, &'static str, &'static str)> {
+    scanner::KEYWORDS.iter().find(|(kw_ty, _, _)| *kw_ty == ty)
+}
+
+fn keyword_named(name: &str) -> Option<&'static str> {
+    scanner::KEYWORDS
+        .iter()
+        .flat_map(|(_, devanagari, roman)| vec![*devanagari, *roman])
+        .chain(scanner::KEYWORD_ALIASES.iter().map(|(alias, _)| *alias))
+        .find(|keyword| *keyword == name)
+}
+
+fn convert_digits(text: &str, to: Script) -> String {
+    text.chars()
+        .map(|c| match to {
+            Script::Devanagari => match c.to_digit(10) {
+                Some(digit) => scanner::DEVANAGARI_DIGITS[digit as usize],
+                None => c,
+            },
+            Script::Roman => match scanner::DEVANAGARI_DIGITS.iter().position(|d| *d == c) {
+                Some(pos) => char::from_digit(pos as u32, 10).unwrap(),
+                None => c,
+            },
+        })
+        .collect()
+}
+
+const CONSONANTS: [(char, &str); 33] = [
+    ('क', "k"),
+    ('ख', "kh"),
+    ('ग', "g"),
+    ('घ', "gh"),
+    ('ङ', "NG"),
+    ('च', "ch"),
+    ('छ', "chh"),
+    ('ज', "j"),
+    ('झ', "jh"),
+    ('ञ', "NY"),
+    ('ट', "T"),
+    ('ठ', "Th"),
+    ('ड', "D"),
+    ('ढ', "Dh"),
+    ('ण', "N"),
+    ('त', "t"),
+    ('थ', "th"),
+    ('द', "d"),
+    ('ध', "dh"),
+    ('न', "n"),
+    ('प', "p"),
+    ('फ', "ph"),
+    ('ब', "b"),
+    ('भ', "bh"),
+    ('म', "m"),
+    ('य', "y"),
+    ('र', "r"),
+    ('ल', "l"),
+    ('व', "w"),
+    ('श', "sh"),
+    ('ष', "Sh"),
+    ('स', "s"),
+    ('ह', "h"),
+];
+
+/// Independent vowel, the sign used after a consonant (none for the inherent 'a'), roman.
+const VOWELS: [(char, Option<char>, &str); 11] = [
+    ('अ', None, "a"),
+    ('आ', Some('ा'), "aa"),
+    ('इ', Some('ि'), "i"),
+    ('ई', Some('ी'), "ii"),
+    ('उ', Some('ु'), "u"),
+    ('ऊ', Some('ू'), "uu"),
+    ('ऋ', Some('ृ'), "Ri"),
+    ('ए', Some('े'), "e"),
+    ('ऐ', Some('ै'), "ai"),
+    ('ओ', Some('ो'), "o"),
+    ('औ', Some('ौ'), "au"),
+];
+
+const SIGNS: [(char, &str); 3] = [('ं', "M"), ('ँ', "MM"), ('ः', "H")];
+
+const VIRAMA: char = '्';
+
+/// `name` in the other script, or `None` if it is already in `to` or has letters we
+/// have no spelling for, in which case it is left alone.
+fn transliterate_name(name: &str, to: Script, digits: bool) -> Option<String> {
+    let res = match to {
+        Script::Roman => to_roman(name)?,
+        Script::Devanagari => to_devanagari(name)?,
+    };
+    if digits {
+        Some(convert_digits(&res, to))
+    } else {
+        Some(res)
+    }
+}
+
+fn to_roman(name: &str) -> Option<String> {
+    if name.is_ascii() {
+        return None;
+    }
+    let chars: Vec<char> = name.chars().collect();
+    let mut res = String::new();
+    let mut idx = 0;
+
+    while idx < chars.len() {
+        let c = chars[idx];
+        idx += 1;
+        if let Some((_, roman)) = CONSONANTS.iter().find(|(d, _)| *d == c) {
+            res.push_str(roman);
+            match chars.get(idx) {
+                Some(&VIRAMA) => idx += 1,
+                Some(next) => match VOWELS.iter().find(|(_, sign, _)| *sign == Some(*next)) {
+                    Some((_, _, vowel)) => {
+                        res.push_str(vowel);
+                        idx += 1;
+                    }
+                    None => res.push('a'),
+                },
+                None => res.push('a'),
+            }
+        } else if let Some((_, _, roman)) = VOWELS.iter().find(|(d, _, _)| *d == c) {
+            res.push_str(roman);
+        } else if let Some((_, roman)) = SIGNS.iter().find(|(d, _)| *d == c) {
+            res.push_str(roman);
+        } else if c.is_ascii_digit() || scanner::DEVANAGARI_DIGITS.contains(&c) {
+            res.push(c);
+        } else {
+            return None;
+        }
+    }
+    Some(res)
+}
+
+fn to_devanagari(name: &str) -> Option<String> {
+    if !name.is_ascii() {
+        return None;
+    }
+    let mut res = String::new();
+    let mut rest = name;
+    // whether the last consonant still needs its vowel (or a virama)
+    let mut bare_consonant = false;
+
+    while !rest.is_empty() {
+        if let Some((c, roman)) = longest_match(rest, CONSONANTS.iter().map(|(c, r)| (*c, *r))) {
+            if bare_consonant {
+                res.push(VIRAMA);
+            }
+            res.push(c);
+            bare_consonant = true;
+            rest = &rest[roman.len()..];
+        } else if let Some((vowel, roman)) =
+            longest_match(rest, VOWELS.iter().map(|(v, _, r)| (*v, *r)))
+        {
+            if bare_consonant {
+                let sign = VOWELS.iter().find(|(v, _, _)| *v == vowel).unwrap().1;
+                res.extend(sign);
+            } else {
+                res.push(vowel);
+            }
+            bare_consonant = false;
+            rest = &rest[roman.len()..];
+        } else {
+            if bare_consonant {
+                res.push(VIRAMA);
+                bare_consonant = false;
+            }
+            match longest_match(rest, SIGNS.iter().map(|(s, r)| (*s, *r))) {
+                Some((sign, roman)) => {
+                    res.push(sign);
+                    rest = &rest[roman.len()..];
+                }
+                None => {
+                    let c = rest.chars().next().unwrap();
+                    if !c.is_ascii_digit() {
+                        return None;
+                    }
+                    res.push(c);
+                    rest = &rest[1..];
+                }
+            }
+        }
+    }
+    if bare_consonant {
+        res.push(VIRAMA);
+    }
+    Some(res)
+}
+
+fn longest_match(
+    text: &str,
+    table: impl Iterator<Item = (char, &'static str)>,
+) -> Option<(char, &'static str)> {
+    table
+        .filter(|(_, roman)| text.starts_with(roman))
+        .max_by_key(|(_, roman)| roman.len())
+}